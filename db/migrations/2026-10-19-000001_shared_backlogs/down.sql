@@ -0,0 +1,3 @@
+ALTER TABLE `tasks` DROP COLUMN `assignee_id`;
+DROP TABLE IF EXISTS `backlog_members`;
+DROP TABLE IF EXISTS `users`;
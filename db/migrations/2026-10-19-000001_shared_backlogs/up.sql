@@ -0,0 +1,15 @@
+CREATE TABLE `users`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`name` TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE `backlog_members`(
+	`backlog_id` TEXT NOT NULL,
+	`user_id` TEXT NOT NULL,
+	`role` TEXT NOT NULL,
+	PRIMARY KEY(backlog_id, user_id),
+	FOREIGN KEY(backlog_id) REFERENCES backlogs(id),
+	FOREIGN KEY(user_id) REFERENCES users(id)
+);
+
+ALTER TABLE `tasks` ADD COLUMN `assignee_id` TEXT REFERENCES users(id);
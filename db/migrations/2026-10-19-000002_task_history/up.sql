@@ -0,0 +1,12 @@
+CREATE TABLE `task_changes`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`task_id` TEXT NOT NULL,
+	`actor_id` TEXT,
+	`kind` TEXT NOT NULL,
+	`changes` TEXT NOT NULL,
+	`changed_at` TIMESTAMP NOT NULL,
+	FOREIGN KEY(task_id) REFERENCES tasks(id),
+	FOREIGN KEY(actor_id) REFERENCES users(id)
+);
+
+CREATE INDEX `task_changes_task_id` ON `task_changes`(`task_id`);
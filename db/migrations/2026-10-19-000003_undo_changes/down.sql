@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS `task_changes_actor_id`;
+ALTER TABLE `task_changes` DROP COLUMN `undone_at`;
@@ -0,0 +1,3 @@
+ALTER TABLE `task_changes` ADD COLUMN `undone_at` TIMESTAMP;
+
+CREATE INDEX `task_changes_actor_id` ON `task_changes`(`actor_id`);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS `capacities`;
+ALTER TABLE `tasks` DROP COLUMN `estimate_minutes`;
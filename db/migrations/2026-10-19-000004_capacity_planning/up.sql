@@ -0,0 +1,12 @@
+ALTER TABLE `tasks` ADD COLUMN `estimate_minutes` INTEGER;
+
+CREATE TABLE `capacities`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`weekday` INTEGER,
+	`date` DATE,
+	`minutes` INTEGER NOT NULL,
+	CHECK((weekday IS NULL) != (date IS NULL))
+);
+
+CREATE UNIQUE INDEX `capacities_weekday` ON `capacities`(`weekday`) WHERE `weekday` IS NOT NULL;
+CREATE UNIQUE INDEX `capacities_date` ON `capacities`(`date`) WHERE `date` IS NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE `tasks` DROP COLUMN `end_time`;
+ALTER TABLE `tasks` DROP COLUMN `start_time`;
@@ -0,0 +1,2 @@
+ALTER TABLE `tasks` ADD COLUMN `start_time` TIME;
+ALTER TABLE `tasks` ADD COLUMN `end_time` TIME;
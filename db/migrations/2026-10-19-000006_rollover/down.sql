@@ -0,0 +1,2 @@
+ALTER TABLE `tasks` DROP COLUMN `rolled_over_at`;
+ALTER TABLE `tasks` DROP COLUMN `postponed_count`;
@@ -0,0 +1,2 @@
+ALTER TABLE `tasks` ADD COLUMN `postponed_count` INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE `tasks` ADD COLUMN `rolled_over_at` TIMESTAMP;
@@ -0,0 +1,9 @@
+CREATE TABLE `saved_views`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`owner_id` TEXT NOT NULL REFERENCES `users`(`id`),
+	`name` TEXT NOT NULL,
+	`filter` TEXT NOT NULL,
+	`upcoming_days` INTEGER
+);
+
+CREATE INDEX `saved_views_owner` ON `saved_views`(`owner_id`);
@@ -0,0 +1 @@
+ALTER TABLE `saved_views` ADD COLUMN `query` TEXT;
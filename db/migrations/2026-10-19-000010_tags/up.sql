@@ -0,0 +1,15 @@
+CREATE TABLE `tags`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`name` TEXT NOT NULL UNIQUE,
+	`color` TEXT NOT NULL
+);
+
+CREATE TABLE `task_tags`(
+	`task_id` TEXT NOT NULL,
+	`tag_id` TEXT NOT NULL,
+	PRIMARY KEY(task_id, tag_id),
+	FOREIGN KEY(task_id) REFERENCES tasks(id),
+	FOREIGN KEY(tag_id) REFERENCES tags(id)
+);
+
+CREATE INDEX `task_tags_tag` ON `task_tags`(`tag_id`);
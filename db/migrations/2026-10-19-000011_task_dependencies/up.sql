@@ -0,0 +1,10 @@
+CREATE TABLE `task_dependencies`(
+	`blocker_id` TEXT NOT NULL,
+	`blocked_id` TEXT NOT NULL,
+	PRIMARY KEY(blocker_id, blocked_id),
+	FOREIGN KEY(blocker_id) REFERENCES tasks(id),
+	FOREIGN KEY(blocked_id) REFERENCES tasks(id),
+	CHECK(blocker_id != blocked_id)
+);
+
+CREATE INDEX `task_dependencies_blocked` ON `task_dependencies`(`blocked_id`);
@@ -0,0 +1,21 @@
+CREATE TABLE `projects`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`name` TEXT NOT NULL,
+	`description` TEXT,
+	`status` TEXT NOT NULL,
+	`target_date` DATE
+);
+
+CREATE TABLE `milestones`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`project_id` TEXT NOT NULL REFERENCES projects(id),
+	`name` TEXT NOT NULL,
+	`due_date` DATE,
+	`position` INTEGER NOT NULL,
+	`completed` BOOL NOT NULL DEFAULT 0
+);
+
+CREATE INDEX `milestones_project` ON `milestones`(`project_id`, `position`);
+
+ALTER TABLE `backlogs` ADD COLUMN `project_id` TEXT REFERENCES projects(id);
+ALTER TABLE `tasks` ADD COLUMN `project_id` TEXT REFERENCES projects(id);
@@ -0,0 +1,2 @@
+ALTER TABLE `tasks` DROP COLUMN `status_id`;
+DROP TABLE IF EXISTS `backlog_columns`;
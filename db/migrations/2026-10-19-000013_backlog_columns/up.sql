@@ -0,0 +1,11 @@
+CREATE TABLE `backlog_columns`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`backlog_id` TEXT NOT NULL REFERENCES backlogs(id),
+	`name` TEXT NOT NULL,
+	`position` INTEGER NOT NULL,
+	`wip_limit` INTEGER
+);
+
+CREATE INDEX `backlog_columns_backlog` ON `backlog_columns`(`backlog_id`, `position`);
+
+ALTER TABLE `tasks` ADD COLUMN `status_id` TEXT REFERENCES backlog_columns(id);
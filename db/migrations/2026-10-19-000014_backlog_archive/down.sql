@@ -0,0 +1,3 @@
+ALTER TABLE `backlogs` DROP COLUMN `deleted_at`;
+ALTER TABLE `backlogs` DROP COLUMN `archived_at`;
+ALTER TABLE `backlogs` DROP COLUMN `position`;
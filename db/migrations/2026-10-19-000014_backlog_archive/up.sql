@@ -0,0 +1,3 @@
+ALTER TABLE `backlogs` ADD COLUMN `position` INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE `backlogs` ADD COLUMN `archived_at` TIMESTAMP;
+ALTER TABLE `backlogs` ADD COLUMN `deleted_at` TIMESTAMP;
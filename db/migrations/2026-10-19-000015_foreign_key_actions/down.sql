@@ -0,0 +1,52 @@
+CREATE TABLE `tasks_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`title` TEXT NOT NULL,
+	`important` BOOLEAN NOT NULL,
+	`urgent` BOOLEAN NOT NULL,
+	`content` TEXT,
+	`completed` BOOLEAN NOT NULL,
+	`role_id` TEXT,
+	`backlog_id` TEXT,
+	`scheduled_date` DATE,
+	`created_at` TIMESTAMP NOT NULL,
+	`updated_at` TIMESTAMP,
+	`deleted_at` TIMESTAMP,
+	`assignee_id` TEXT REFERENCES users(id),
+	`estimate_minutes` INTEGER,
+	`start_time` TIME,
+	`end_time` TIME,
+	`postponed_count` INTEGER NOT NULL DEFAULT 0,
+	`rolled_over_at` TIMESTAMP,
+	`recurrence` TEXT,
+	`project_id` TEXT REFERENCES projects(id),
+	`status_id` TEXT REFERENCES backlog_columns(id),
+	FOREIGN KEY(role_id) REFERENCES roles(id),
+	FOREIGN KEY(backlog_id) REFERENCES backlogs(id)
+);
+INSERT INTO `tasks_new`(`id`, `title`, `important`, `urgent`, `content`, `completed`, `role_id`, `backlog_id`, `scheduled_date`, `created_at`, `updated_at`, `deleted_at`, `assignee_id`, `estimate_minutes`, `start_time`, `end_time`, `postponed_count`, `rolled_over_at`, `recurrence`, `project_id`, `status_id`) SELECT `id`, `title`, `important`, `urgent`, `content`, `completed`, `role_id`, `backlog_id`, `scheduled_date`, `created_at`, `updated_at`, `deleted_at`, `assignee_id`, `estimate_minutes`, `start_time`, `end_time`, `postponed_count`, `rolled_over_at`, `recurrence`, `project_id`, `status_id` FROM `tasks`;
+DROP TABLE `tasks`;
+ALTER TABLE `tasks_new` RENAME TO `tasks`;
+
+CREATE TABLE `backlog_members_new`(
+	`backlog_id` TEXT NOT NULL,
+	`user_id` TEXT NOT NULL,
+	`role` TEXT NOT NULL,
+	PRIMARY KEY(backlog_id, user_id),
+	FOREIGN KEY(backlog_id) REFERENCES backlogs(id),
+	FOREIGN KEY(user_id) REFERENCES users(id)
+);
+INSERT INTO `backlog_members_new` SELECT `backlog_id`, `user_id`, `role` FROM `backlog_members`;
+DROP TABLE `backlog_members`;
+ALTER TABLE `backlog_members_new` RENAME TO `backlog_members`;
+
+CREATE TABLE `backlog_columns_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`backlog_id` TEXT NOT NULL REFERENCES backlogs(id),
+	`name` TEXT NOT NULL,
+	`position` INTEGER NOT NULL,
+	`wip_limit` INTEGER
+);
+INSERT INTO `backlog_columns_new` SELECT `id`, `backlog_id`, `name`, `position`, `wip_limit` FROM `backlog_columns`;
+DROP TABLE `backlog_columns`;
+ALTER TABLE `backlog_columns_new` RENAME TO `backlog_columns`;
+CREATE INDEX `backlog_columns_backlog` ON `backlog_columns`(`backlog_id`, `position`);
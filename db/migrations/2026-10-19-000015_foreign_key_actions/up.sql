@@ -0,0 +1,62 @@
+-- SQLite can't change the actions of an existing foreign key, so the tables are rebuilt.
+-- References that already dangle are cleared first, or the copies would violate them.
+UPDATE `tasks` SET `role_id` = NULL WHERE `role_id` NOT IN (SELECT `id` FROM `roles`);
+UPDATE `tasks` SET `backlog_id` = NULL WHERE `backlog_id` NOT IN (SELECT `id` FROM `backlogs`);
+UPDATE `tasks` SET `assignee_id` = NULL WHERE `assignee_id` NOT IN (SELECT `id` FROM `users`);
+UPDATE `tasks` SET `project_id` = NULL WHERE `project_id` NOT IN (SELECT `id` FROM `projects`);
+UPDATE `tasks` SET `status_id` = NULL WHERE `status_id` NOT IN (SELECT `id` FROM `backlog_columns`);
+DELETE FROM `backlog_members` WHERE `backlog_id` NOT IN (SELECT `id` FROM `backlogs`) OR `user_id` NOT IN (SELECT `id` FROM `users`);
+DELETE FROM `backlog_columns` WHERE `backlog_id` NOT IN (SELECT `id` FROM `backlogs`);
+
+CREATE TABLE `tasks_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`title` TEXT NOT NULL,
+	`important` BOOLEAN NOT NULL,
+	`urgent` BOOLEAN NOT NULL,
+	`content` TEXT,
+	`completed` BOOLEAN NOT NULL,
+	`role_id` TEXT,
+	`backlog_id` TEXT,
+	`scheduled_date` DATE,
+	`created_at` TIMESTAMP NOT NULL,
+	`updated_at` TIMESTAMP,
+	`deleted_at` TIMESTAMP,
+	`assignee_id` TEXT REFERENCES users(id) ON DELETE SET NULL,
+	`estimate_minutes` INTEGER,
+	`start_time` TIME,
+	`end_time` TIME,
+	`postponed_count` INTEGER NOT NULL DEFAULT 0,
+	`rolled_over_at` TIMESTAMP,
+	`recurrence` TEXT,
+	`project_id` TEXT REFERENCES projects(id) ON DELETE SET NULL,
+	`status_id` TEXT REFERENCES backlog_columns(id) ON DELETE SET NULL,
+	FOREIGN KEY(role_id) REFERENCES roles(id) ON DELETE SET NULL,
+	FOREIGN KEY(backlog_id) REFERENCES backlogs(id) ON DELETE SET NULL
+);
+INSERT INTO `tasks_new`(`id`, `title`, `important`, `urgent`, `content`, `completed`, `role_id`, `backlog_id`, `scheduled_date`, `created_at`, `updated_at`, `deleted_at`, `assignee_id`, `estimate_minutes`, `start_time`, `end_time`, `postponed_count`, `rolled_over_at`, `recurrence`, `project_id`, `status_id`) SELECT `id`, `title`, `important`, `urgent`, `content`, `completed`, `role_id`, `backlog_id`, `scheduled_date`, `created_at`, `updated_at`, `deleted_at`, `assignee_id`, `estimate_minutes`, `start_time`, `end_time`, `postponed_count`, `rolled_over_at`, `recurrence`, `project_id`, `status_id` FROM `tasks`;
+DROP TABLE `tasks`;
+ALTER TABLE `tasks_new` RENAME TO `tasks`;
+
+CREATE TABLE `backlog_members_new`(
+	`backlog_id` TEXT NOT NULL,
+	`user_id` TEXT NOT NULL,
+	`role` TEXT NOT NULL,
+	PRIMARY KEY(backlog_id, user_id),
+	FOREIGN KEY(backlog_id) REFERENCES backlogs(id) ON DELETE CASCADE,
+	FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+INSERT INTO `backlog_members_new` SELECT `backlog_id`, `user_id`, `role` FROM `backlog_members`;
+DROP TABLE `backlog_members`;
+ALTER TABLE `backlog_members_new` RENAME TO `backlog_members`;
+
+CREATE TABLE `backlog_columns_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`backlog_id` TEXT NOT NULL REFERENCES backlogs(id) ON DELETE CASCADE,
+	`name` TEXT NOT NULL,
+	`position` INTEGER NOT NULL,
+	`wip_limit` INTEGER
+);
+INSERT INTO `backlog_columns_new` SELECT `id`, `backlog_id`, `name`, `position`, `wip_limit` FROM `backlog_columns`;
+DROP TABLE `backlog_columns`;
+ALTER TABLE `backlog_columns_new` RENAME TO `backlog_columns`;
+CREATE INDEX `backlog_columns_backlog` ON `backlog_columns`(`backlog_id`, `position`);
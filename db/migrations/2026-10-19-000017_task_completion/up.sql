@@ -0,0 +1,3 @@
+ALTER TABLE `tasks` ADD COLUMN `completed_at` TIMESTAMP;
+ALTER TABLE `tasks` ADD COLUMN `archived_at` TIMESTAMP;
+UPDATE `tasks` SET `completed_at` = COALESCE(`updated_at`, `created_at`) WHERE `completed`;
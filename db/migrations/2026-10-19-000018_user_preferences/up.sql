@@ -0,0 +1,4 @@
+CREATE TABLE `user_preferences`(
+	`user_id` TEXT NOT NULL PRIMARY KEY REFERENCES `users`(`id`) ON DELETE CASCADE,
+	`preferences` TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- The owners given to memberless backlogs are kept, as they can't be told apart from others
+DROP TABLE `sessions`;
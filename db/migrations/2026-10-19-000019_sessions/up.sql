@@ -0,0 +1,14 @@
+CREATE TABLE `sessions`(
+	`token` TEXT NOT NULL PRIMARY KEY,
+	`user_id` TEXT NOT NULL REFERENCES `users`(`id`) ON DELETE CASCADE,
+	`created_at` TIMESTAMP NOT NULL,
+	`expires_at` TIMESTAMP NOT NULL
+);
+
+-- Backlogs from before sharing have no members and were open to everyone. They now belong to
+-- the first user, who is the one that used the planner before there were several.
+INSERT INTO `backlog_members`(`backlog_id`, `user_id`, `role`)
+SELECT `backlogs`.`id`, (SELECT `id` FROM `users` ORDER BY `id` LIMIT 1), 'owner'
+FROM `backlogs`
+WHERE EXISTS (SELECT 1 FROM `users`)
+	AND NOT EXISTS (SELECT 1 FROM `backlog_members` WHERE `backlog_members`.`backlog_id` = `backlogs`.`id`);
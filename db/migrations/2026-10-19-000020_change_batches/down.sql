@@ -0,0 +1,2 @@
+DROP INDEX `task_changes_batch_id`;
+ALTER TABLE `task_changes` DROP COLUMN `batch_id`;
@@ -0,0 +1,4 @@
+-- Changes made by one action, e.g. a bulk edit, share a batch and are undone together
+ALTER TABLE `task_changes` ADD COLUMN `batch_id` TEXT NOT NULL DEFAULT '';
+UPDATE `task_changes` SET `batch_id` = `id`;
+CREATE INDEX `task_changes_batch_id` ON `task_changes`(`batch_id`);
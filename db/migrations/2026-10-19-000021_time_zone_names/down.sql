@@ -0,0 +1,8 @@
+UPDATE `user_preferences` SET `preferences` = json_remove(
+	json_set(`preferences`, '$.utc_offset_minutes', CASE
+		WHEN json_extract(`preferences`, '$.time_zone') LIKE 'Etc/GMT_%'
+			THEN -CAST(substr(json_extract(`preferences`, '$.time_zone'), 8) AS INTEGER) * 60
+		ELSE 0
+	END),
+	'$.time_zone'
+);
@@ -0,0 +1,11 @@
+-- Preferences name the time zone instead of storing a fixed offset. Whole-hour offsets map to the
+-- fixed Etc/GMT zones, whose sign is inverted: UTC+02:00 is Etc/GMT-2. Others fall back to UTC.
+UPDATE `user_preferences` SET `preferences` = json_remove(
+	json_set(`preferences`, '$.time_zone', CASE
+		WHEN coalesce(json_extract(`preferences`, '$.utc_offset_minutes'), 0) = 0 THEN 'UTC'
+		WHEN json_extract(`preferences`, '$.utc_offset_minutes') % 60 = 0
+			THEN printf('Etc/GMT%+d', -json_extract(`preferences`, '$.utc_offset_minutes') / 60)
+		ELSE 'UTC'
+	END),
+	'$.utc_offset_minutes'
+);
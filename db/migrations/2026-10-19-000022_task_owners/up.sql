@@ -0,0 +1,6 @@
+ALTER TABLE `tasks` ADD COLUMN `owner_id` TEXT REFERENCES `users`(`id`) ON DELETE SET NULL;
+CREATE INDEX `tasks_owner_id` ON `tasks`(`owner_id`);
+
+-- Tasks so far were created by the first user, the one that used the planner before there were
+-- several, so the inbox tasks among them stay in that user's inbox.
+UPDATE `tasks` SET `owner_id` = (SELECT `id` FROM `users` ORDER BY `id` LIMIT 1);
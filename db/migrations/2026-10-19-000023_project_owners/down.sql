@@ -0,0 +1,2 @@
+DROP INDEX `projects_owner_id`;
+ALTER TABLE `projects` DROP COLUMN `owner_id`;
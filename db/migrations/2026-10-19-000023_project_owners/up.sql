@@ -0,0 +1,5 @@
+ALTER TABLE `projects` ADD COLUMN `owner_id` TEXT REFERENCES `users`(`id`) ON DELETE SET NULL;
+CREATE INDEX `projects_owner_id` ON `projects`(`owner_id`);
+
+-- Projects so far were made by the first user, like the tasks before them.
+UPDATE `projects` SET `owner_id` = (SELECT `id` FROM `users` ORDER BY `id` LIMIT 1);
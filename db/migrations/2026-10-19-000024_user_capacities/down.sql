@@ -0,0 +1,17 @@
+-- Only the first user's capacities stay, as there is one set for everyone again.
+CREATE TABLE `capacities_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`weekday` INTEGER,
+	`date` DATE,
+	`minutes` INTEGER NOT NULL,
+	CHECK((weekday IS NULL) != (date IS NULL))
+);
+INSERT INTO `capacities_new`(`id`, `weekday`, `date`, `minutes`)
+SELECT `id`, `weekday`, `date`, `minutes`
+FROM `capacities`
+WHERE `user_id` = (SELECT `id` FROM `users` ORDER BY `id` LIMIT 1);
+DROP TABLE `capacities`;
+ALTER TABLE `capacities_new` RENAME TO `capacities`;
+
+CREATE UNIQUE INDEX `capacities_weekday` ON `capacities`(`weekday`) WHERE `weekday` IS NOT NULL;
+CREATE UNIQUE INDEX `capacities_date` ON `capacities`(`date`) WHERE `date` IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Capacity is set per user now. SQLite can't add a required reference to an existing table, so
+-- it is rebuilt; the capacities so far were set by the first user and stay with them.
+CREATE TABLE `capacities_new`(
+	`id` TEXT NOT NULL PRIMARY KEY,
+	`user_id` TEXT NOT NULL REFERENCES `users`(`id`) ON DELETE CASCADE,
+	`weekday` INTEGER,
+	`date` DATE,
+	`minutes` INTEGER NOT NULL,
+	CHECK((weekday IS NULL) != (date IS NULL))
+);
+INSERT INTO `capacities_new`(`id`, `user_id`, `weekday`, `date`, `minutes`)
+SELECT `id`, (SELECT `id` FROM `users` ORDER BY `id` LIMIT 1), `weekday`, `date`, `minutes`
+FROM `capacities`
+WHERE EXISTS (SELECT 1 FROM `users`);
+DROP TABLE `capacities`;
+ALTER TABLE `capacities_new` RENAME TO `capacities`;
+
+CREATE UNIQUE INDEX `capacities_weekday` ON `capacities`(`user_id`, `weekday`) WHERE `weekday` IS NOT NULL;
+CREATE UNIQUE INDEX `capacities_date` ON `capacities`(`user_id`, `date`) WHERE `date` IS NOT NULL;
@@ -0,0 +1,93 @@
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use super::model::*;
+
+/// Suggests moves that bring overloaded days back within capacity.
+///
+/// Open tasks with an estimate are moved off each overloaded day, largest first, to the day in
+/// `days` with the most time left that can still fit them. Days before `earliest` are never
+/// proposed as a target, so work isn't rescheduled into the past.
+pub fn rebalance(days: &[NaiveDate], tasks: &[Task], capacities: &[Capacity], earliest: NaiveDate) -> Vec<RebalanceSuggestion> {
+    let mut load: BTreeMap<NaiveDate, i32> = days.iter().map(|day| (*day, 0)).collect();
+    for task in tasks.iter().filter(|t| !t.completed) {
+        if let (Some(day), Some(estimate)) = (task.scheduled_date, task.estimate_minutes) {
+            if let Some(minutes) = load.get_mut(&day) {
+                *minutes += estimate;
+            }
+        }
+    }
+
+    let mut suggestions = vec![];
+    for from in days {
+        let mut movable: Vec<&Task> = tasks
+            .iter()
+            .filter(|t| !t.completed && t.scheduled_date == Some(*from) && t.estimate_minutes.is_some())
+            .collect();
+        movable.sort_by_key(|t| std::cmp::Reverse(t.estimate_minutes));
+
+        for task in movable {
+            if load[from] <= capacity_on(capacities, *from) {
+                break;
+            }
+            let estimate = task.estimate_minutes.unwrap_or_default();
+
+            let target = days
+                .iter()
+                .filter(|day| *day != from && **day >= earliest)
+                .map(|day| (*day, capacity_on(capacities, *day) - load[day]))
+                .filter(|(_, free)| *free >= estimate)
+                .max_by_key(|(_, free)| *free);
+
+            if let Some((to, _)) = target {
+                *load.get_mut(from).unwrap() -= estimate;
+                *load.get_mut(&to).unwrap() += estimate;
+                suggestions.push(RebalanceSuggestion { task: task.clone(), from: *from, to });
+            }
+        }
+    }
+
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::tests::{date, id, task};
+
+    /// Monday the 10th to Wednesday the 12th of March 2025.
+    fn days() -> Vec<NaiveDate> {
+        vec![date(10), date(11), date(12)]
+    }
+
+    fn planned(title: &str, day: u32, minutes: i32) -> Task {
+        Task { scheduled_date: Some(date(day)), estimate_minutes: Some(minutes), ..task(title) }
+    }
+
+    fn moves(suggestions: &[RebalanceSuggestion]) -> Vec<(&str, NaiveDate, NaiveDate)> {
+        suggestions.iter().map(|s| (s.task.title.as_str(), s.from, s.to)).collect()
+    }
+
+    #[test]
+    fn largest_task_moves_to_the_lightest_day() {
+        let tasks = [planned("big", 10, 300), planned("small", 10, 240), planned("busy", 11, 400)];
+        let suggestions = rebalance(&days(), &tasks, &[], date(10));
+        assert_eq!(moves(&suggestions), [("big", date(10), date(12))]);
+    }
+
+    #[test]
+    fn days_before_earliest_are_not_targets() {
+        let tasks = [planned("big", 11, 300), planned("small", 11, 240), planned("busy", 12, 400)];
+        assert_eq!(moves(&rebalance(&days(), &tasks, &[], date(10))), [("big", date(11), date(10))]);
+        assert!(rebalance(&days(), &tasks, &[], date(11)).is_empty());
+    }
+
+    #[test]
+    fn completed_tasks_and_date_capacity_count() {
+        let monday = Capacity { id: id(), user_id: id(), weekday: Some(0), date: None, minutes: 60 };
+        let tenth = Capacity { id: id(), user_id: id(), weekday: None, date: Some(date(10)), minutes: 120 };
+        let tasks = [planned("open", 10, 90), Task { completed: true, ..planned("done", 10, 60) }, planned("later", 12, 30)];
+
+        assert_eq!(moves(&rebalance(&days(), &tasks, &[monday.clone()], date(10))), [("open", date(10), date(11))]);
+        assert!(rebalance(&days(), &tasks, &[monday, tenth], date(10)).is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use super::model::Task;
+use super::preferences::Preferences;
+
+const USAGE: &str = "Usage: planner query '<query>', e.g. planner query 'is:open due<+7d !urgent'
+       planner check-integrity [--repair]
+       planner sign-in <name>";
+
+/// Handles command line invocations such as `planner query 'role:work is:open'`.
+///
+/// Returns the exit code when the arguments name a command, or `None` to start the app as usual.
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    if command != "query" && command != "check-integrity" && command != "sign-in" {
+        return None;
+    }
+
+    let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            return Some(1);
+        }
+    };
+
+    if command == "check-integrity" {
+        let repair = args.get(1).is_some_and(|arg| arg == "--repair");
+        return match runtime.block_on(super::server::command_line_integrity_check(repair)) {
+            Ok(report) => {
+                println!("{}", report);
+                // Unrepaired problems fail the command, so it can be used in scripts
+                Some(if report.is_clean() || report.repaired { 0 } else { 1 })
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                Some(1)
+            },
+        };
+    }
+
+    if command == "sign-in" {
+        let Some(user_name) = args.get(1) else {
+            eprintln!("{}", USAGE);
+            return Some(2);
+        };
+        return match runtime.block_on(super::server::command_line_session(user_name)) {
+            Ok(token) => {
+                println!("Sign in as {} with this token: {}", user_name, token);
+                Some(0)
+            },
+            Err(e) => {
+                eprintln!("{}", e);
+                Some(1)
+            },
+        };
+    }
+
+    let Some(input) = args.get(1) else {
+        eprintln!("{}", USAGE);
+        return Some(2);
+    };
+
+    match runtime.block_on(super::server::command_line_query(input)) {
+        Ok((tasks, preferences)) => {
+            for task in tasks {
+                println!("{}", describe(&task, &preferences));
+            }
+            Some(0)
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(1)
+        },
+    }
+}
+
+fn describe(task: &Task, preferences: &Preferences) -> String {
+    let date = task.scheduled_date.map(|d| preferences.format_date(d)).unwrap_or_else(|| "-".repeat(10));
+    let done = if task.completed { "x" } else { " " };
+    let mut flags = String::new();
+    if task.important {
+        flags.push('!');
+    }
+    if task.urgent {
+        flags.push_str("!!");
+    }
+    format!("{} [{}] {} {}", date, done, task.title, flags).trim_end().to_string()
+}
@@ -0,0 +1,62 @@
+use std::collections::{HashMap, HashSet};
+use super::model::*;
+
+/// Whether adding `blocker` → `blocked` to `dependencies` would close a cycle, that is whether
+/// `blocker` already waits for `blocked`, directly or through other tasks.
+pub fn creates_cycle(dependencies: &[TaskDependency], blocker: Id, blocked: Id) -> bool {
+    if blocker == blocked {
+        return true;
+    }
+
+    let mut blocks: HashMap<Id, Vec<Id>> = HashMap::new();
+    for dependency in dependencies {
+        blocks.entry(dependency.blocker_id).or_default().push(dependency.blocked_id);
+    }
+
+    // Walk everything that waits for `blocked`; reaching `blocker` means it waits for itself
+    let mut seen = HashSet::new();
+    let mut pending = vec![blocked];
+    while let Some(task) = pending.pop() {
+        if task == blocker {
+            return true;
+        }
+        if seen.insert(task) {
+            pending.extend(blocks.get(&task).into_iter().flatten().copied());
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::tests::id;
+
+    fn link(blocker_id: Id, blocked_id: Id) -> TaskDependency {
+        TaskDependency { blocker_id, blocked_id }
+    }
+
+    #[test]
+    fn direct_cycle() {
+        let (a, b) = (id(), id());
+        let dependencies = [link(a, b)];
+        assert!(creates_cycle(&dependencies, b, a));
+        assert!(!creates_cycle(&dependencies, a, b));
+    }
+
+    #[test]
+    fn transitive_cycle() {
+        let (a, b, c, d) = (id(), id(), id(), id());
+        let dependencies = [link(a, b), link(b, c)];
+        assert!(creates_cycle(&dependencies, c, a));
+        assert!(!creates_cycle(&dependencies, a, c));
+        assert!(!creates_cycle(&dependencies, d, a));
+        assert!(!creates_cycle(&dependencies, c, d));
+    }
+
+    #[test]
+    fn self_link() {
+        let a = id();
+        assert!(creates_cycle(&[], a, a));
+    }
+}
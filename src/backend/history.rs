@@ -0,0 +1,179 @@
+use dioxus::prelude::*;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use chrono::NaiveDateTime;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+use super::model::*;
+use super::server::DbConnection;
+use super::preferences::utc_now;
+
+/// Fields that change on every write and would only add noise to the history.
+const IGNORED_FIELDS: [&str; 2] = ["updated_at", "version"];
+
+fn task_fields(task: &Task) -> Result<Map<String, Value>, ServerFnError> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(fields)) => Ok(fields),
+        Ok(_) => Err(ServerFnError::new("Task did not serialize to an object")),
+        Err(e) => Err(ServerFnError::new(format!("Task serialize error: {}", e))),
+    }
+}
+
+/// Field-level differences between two versions of a task.
+///
+/// Without a previous version every field counts as changed, which is how creation is recorded.
+pub fn diff_tasks(before: Option<&Task>, after: &Task) -> Result<Vec<FieldChange>, ServerFnError> {
+    let before = match before {
+        Some(task) => task_fields(task)?,
+        None => Map::new(),
+    };
+
+    let changes = task_fields(after)?
+        .into_iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, after)| {
+            let before = before.get(&field).cloned().unwrap_or(Value::Null);
+            (before != after).then_some(FieldChange { field, before, after })
+        })
+        .collect();
+
+    Ok(changes)
+}
+
+/// Applies one side of a set of field changes to a task.
+///
+/// With `use_before` the task gets the values it had before the changes were made, which is how
+/// changes are reverted; otherwise the changes are replayed.
+pub fn apply_changes(task: &Task, changes: &[FieldChange], use_before: bool) -> Result<Task, ServerFnError> {
+    let mut fields = task_fields(task)?;
+    for change in changes {
+        let value = if use_before { &change.before } else { &change.after };
+        fields.insert(change.field.clone(), value.clone());
+    }
+
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|e| ServerFnError::new(format!("Task deserialize error: {}", e)))
+}
+
+/// A new batch to record the changes of one action in.
+pub fn new_batch() -> Id {
+    Id(Uuid::now_v7())
+}
+
+/// Stores a change log row as part of `batch`, unless nothing actually changed.
+pub async fn record_change(conn: &mut DbConnection, task: Id, actor: Option<Id>, kind: ChangeKind, changes: Vec<FieldChange>, batch: Id) -> Result<Option<TaskChange>, ServerFnError> {
+    use super::schema::task_changes;
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let change = TaskChange {
+        id: Id(Uuid::now_v7()),
+        task_id: task,
+        actor_id: actor,
+        kind,
+        changes: FieldChanges(changes),
+        changed_at: utc_now(),
+        undone_at: None,
+        batch_id: batch,
+    };
+
+    diesel::insert_into(task_changes::table)
+        .values(&change)
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    Ok(Some(change))
+}
+
+/// All changes of a task, oldest first. Ids are v7 UUIDs, so they sort by creation time.
+pub async fn task_changes_of(conn: &mut DbConnection, task: Id) -> Result<Vec<TaskChange>, ServerFnError> {
+    use super::schema::task_changes::dsl::*;
+
+    task_changes
+        .filter(task_id.eq(task))
+        .order(id.asc())
+        .select(TaskChange::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// The changes of `actor` made in `batch`, oldest first.
+pub async fn batch_changes(conn: &mut DbConnection, actor: Id, batch: Id) -> Result<Vec<TaskChange>, ServerFnError> {
+    use super::schema::task_changes::dsl::*;
+
+    task_changes
+        .filter(batch_id.eq(batch))
+        .filter(actor_id.eq(actor))
+        .order(id.asc())
+        .select(TaskChange::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// The most recent change of `actor` that is still in effect, whose batch is what undo reverts.
+pub async fn last_active_change(conn: &mut DbConnection, actor: Id) -> Result<Option<TaskChange>, ServerFnError> {
+    use super::schema::task_changes::dsl::*;
+
+    task_changes
+        .filter(actor_id.eq(actor))
+        .filter(undone_at.is_null())
+        .order(id.desc())
+        .select(TaskChange::as_select())
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// The change of `actor` whose batch redo should reapply: the one undone last, as long as no new
+/// change was made since. Making a change after undoing therefore clears the redo stack.
+pub async fn next_redo_change(conn: &mut DbConnection, actor: Id) -> Result<Option<TaskChange>, ServerFnError> {
+    use super::schema::task_changes::dsl::*;
+
+    let mut query = task_changes
+        .filter(actor_id.eq(actor))
+        .filter(undone_at.is_not_null())
+        .order(undone_at.desc())
+        .select(TaskChange::as_select())
+        .into_boxed();
+
+    if let Some(active) = last_active_change(conn, actor).await? {
+        query = query.filter(undone_at.gt(active.changed_at));
+    }
+
+    query
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+pub async fn set_undone(conn: &mut DbConnection, change: Id, undone: Option<NaiveDateTime>) -> Result<(), ServerFnError> {
+    use super::schema::task_changes::dsl::*;
+
+    diesel::update(task_changes.find(change))
+        .set(undone_at.eq(undone))
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
+
+    Ok(())
+}
+
+/// The task as it was before (`undo`) or after (`!undo`) a change.
+///
+/// Creation records every field as new, so it is undone by deleting the task instead.
+pub fn task_for_undo(task: &Task, change: &TaskChange, undo: bool) -> Result<Task, ServerFnError> {
+    match change.kind {
+        ChangeKind::Created => Ok(Task {
+            deleted_at: if undo { Some(utc_now()) } else { None },
+            ..task.clone()
+        }),
+        _ => apply_changes(task, &change.changes.0, undo),
+    }
+}
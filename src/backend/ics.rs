@@ -0,0 +1,116 @@
+use chrono::{Days, NaiveDateTime};
+use super::model::*;
+
+const DATE_FORMAT: &str = "%Y%m%d";
+const DATE_TIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+/// Longest line allowed by RFC 5545, in octets without the line break.
+const LINE_OCTETS: usize = 75;
+
+/// Escapes text values as required by RFC 5545.
+fn escape(text: &str) -> String {
+    // Line breaks of any kind end up as an escaped \n; a stray CR would break the line
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line longer than 75 octets into several, each continuation starting with a
+/// space as RFC 5545 requires. Lines are only broken between characters, never inside one.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the continuation line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+/// Renders scheduled tasks as an iCalendar document.
+///
+/// Time-blocked tasks become timed events in floating local time, other tasks all-day events.
+/// `name` is the calendar name shown by clients that support it.
+pub fn to_ics(tasks: &[Task], stamp: NaiveDateTime, name: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//planner//EN".to_string(),
+        format!("X-WR-CALNAME:{}", escape(name)),
+    ];
+
+    for task in tasks {
+        let Some(day) = task.scheduled_date else {
+            continue;
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@planner", task.id.0));
+        lines.push(format!("DTSTAMP:{}Z", stamp.format(DATE_TIME_FORMAT)));
+        match task.time_block() {
+            Some((start, end)) => {
+                lines.push(format!("DTSTART:{}", day.and_time(start).format(DATE_TIME_FORMAT)));
+                lines.push(format!("DTEND:{}", day.and_time(end).format(DATE_TIME_FORMAT)));
+            },
+            None => {
+                let next_day = day.checked_add_days(Days::new(1)).unwrap_or(day);
+                lines.push(format!("DTSTART;VALUE=DATE:{}", day.format(DATE_FORMAT)));
+                lines.push(format!("DTEND;VALUE=DATE:{}", next_day.format(DATE_FORMAT)));
+            },
+        }
+        lines.push(format!("SUMMARY:{}", escape(&task.title)));
+        if let Some(content) = &task.content {
+            lines.push(format!("DESCRIPTION:{}", escape(content)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| fold(line) + "\r\n").collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_breaks_are_escaped() {
+        assert_eq!(escape("one\r\ntwo\rthree\nfour"), "one\\ntwo\\nthree\\nfour");
+        assert_eq!(escape("a;b,c\\d"), "a\\;b\\,c\\\\d");
+        assert!(!escape("notes\r\n\r\n").contains('\r'));
+    }
+
+    #[test]
+    fn short_lines_are_left_alone() {
+        let line = "SUMMARY:Pay rent";
+        assert_eq!(fold(line), line);
+        assert_eq!(fold(&"x".repeat(LINE_OCTETS)), "x".repeat(LINE_OCTETS));
+    }
+
+    #[test]
+    fn long_lines_are_folded_at_75_octets() {
+        let line = "x".repeat(200);
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert!(parts.iter().all(|part| part.len() <= LINE_OCTETS));
+        assert!(parts[1..].iter().all(|part| part.starts_with(' ')));
+        assert_eq!(parts.iter().map(|part| part.trim_start_matches(' ')).collect::<String>(), line);
+    }
+
+    #[test]
+    fn multi_byte_characters_are_not_split() {
+        let line = "é".repeat(100);
+        let folded = fold(&line);
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -0,0 +1,69 @@
+//! Finds and clears task references to roles and backlogs that no longer exist.
+//!
+//! Foreign keys are enforced now, but databases written before that can still hold such rows.
+
+use dioxus::prelude::*;
+use diesel::prelude::*;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use super::model::*;
+use super::history::{diff_tasks, new_batch, record_change};
+use super::server::{DbConnection, TransactionError, store_task};
+
+pub async fn check(conn: &mut DbConnection, repair: bool) -> Result<IntegrityReport, ServerFnError> {
+    use super::schema::{backlogs, roles, tasks};
+
+    let dangling_roles = tasks::table
+        .filter(tasks::role_id.is_not_null())
+        .filter(diesel::dsl::not(tasks::role_id.eq_any(roles::table.select(roles::id.nullable()))))
+        .select(Task::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let dangling_backlogs = tasks::table
+        .filter(tasks::backlog_id.is_not_null())
+        .filter(diesel::dsl::not(tasks::backlog_id.eq_any(backlogs::table.select(backlogs::id.nullable()))))
+        .select(Task::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let report = IntegrityReport {
+        dangling_roles: dangling_roles.iter().map(|t| t.id).collect(),
+        dangling_backlogs: dangling_backlogs.iter().map(|t| t.id).collect(),
+        repaired: repair,
+    };
+    if !repair {
+        return Ok(report);
+    }
+
+    // Loaded again per task, as one task can have both references dangling. All in one
+    // transaction, so a failed repair leaves the tasks as they were.
+    let batch = new_batch();
+    let (roles_gone, backlogs_gone) = (&report.dangling_roles, &report.dangling_backlogs);
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        for task_id in roles_gone.iter().chain(backlogs_gone) {
+            let existing = tasks::table
+                .find(*task_id)
+                .select(Task::as_select())
+                .first(conn)
+                .await?;
+            let repaired = Task {
+                role_id: existing.role_id.filter(|_| !roles_gone.contains(task_id)),
+                backlog_id: existing.backlog_id.filter(|_| !backlogs_gone.contains(task_id)),
+                status_id: existing.status_id.filter(|_| !backlogs_gone.contains(task_id)),
+                ..existing.clone()
+            };
+            if repaired == existing {
+                continue;
+            }
+            let repaired = store_task(conn, repaired).await?;
+            record_change(conn, repaired.id, None, ChangeKind::Updated, diff_tasks(Some(&existing), &repaired)?, batch).await?;
+        }
+        Ok(())
+    }.scope_boxed())
+    .await?;
+
+    Ok(report)
+}
@@ -1,5 +1,36 @@
 pub mod model;
 pub mod server;
+pub mod query;
+pub mod quick_add;
+pub mod validation;
+pub mod preferences;
 
 #[cfg(feature = "server")]
-mod schema;
\ No newline at end of file
+mod schema;
+
+#[cfg(feature = "server")]
+mod permissions;
+
+#[cfg(feature = "server")]
+mod history;
+
+#[cfg(feature = "server")]
+mod capacity;
+
+#[cfg(feature = "server")]
+mod ics;
+
+#[cfg(feature = "server")]
+mod dependencies;
+
+#[cfg(feature = "server")]
+mod project;
+
+#[cfg(feature = "server")]
+mod integrity;
+
+#[cfg(feature = "server")]
+pub mod rollover;
+
+#[cfg(feature = "server")]
+pub mod cli;
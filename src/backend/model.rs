@@ -1,10 +1,11 @@
-use chrono::{NaiveDateTime, NaiveDate};
+use chrono::{Datelike, Days, NaiveDateTime, NaiveDate, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "server")]
 use diesel::prelude::*;
 #[cfg(feature = "server")]
 use super::schema::*;
 use uuid::Uuid;
+use super::preferences::Preferences;
 #[cfg(feature = "server")]
 use diesel::deserialize::FromSql;
 #[cfg(feature = "server")]
@@ -16,7 +17,7 @@ use diesel::sqlite::Sqlite;
 #[cfg(feature = "server")]
 use diesel::{AsExpression, FromSqlRow, backend::Backend};
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
 #[cfg_attr(feature = "server", diesel(sql_type = Text))]
 pub struct Id(pub Uuid);
@@ -55,12 +56,530 @@ pub struct Task {
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
     pub deleted_at: Option<NaiveDateTime>,
+    pub assignee_id: Option<Id>,
+    pub estimate_minutes: Option<i32>,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    /// How many times the task was rolled over after being left open on its day.
+    pub postponed_count: i32,
+    /// Set when an open task was copied forward, so it isn't copied again.
+    pub rolled_over_at: Option<NaiveDateTime>,
+    /// Completing a recurring task schedules its next occurrence.
+    pub recurrence: Option<Recurrence>,
+    /// Project of the task itself; tasks in a backlog also belong to the backlog's project.
+    pub project_id: Option<Id>,
+    /// Column of the task on its backlog's board. Separate from `completed`, so a task can be
+    /// done without having moved along, and tasks without one are shown in the first column.
+    pub status_id: Option<Id>,
+    /// Goes up with every write, so a client can tell its copy of the task is stale.
+    pub version: i32,
+    /// When the task was last ticked off; cleared again when it is reopened.
+    pub completed_at: Option<NaiveDateTime>,
+    /// Set on completed tasks that were put away, which keeps them out of every list but the archive.
+    pub archived_at: Option<NaiveDateTime>,
+    /// Who created the task. Tasks outside a backlog are only visible to them.
+    pub owner_id: Option<Id>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct TaskFilter {
+impl Task {
+    /// Start and end of the task's time block, if it has one.
+    pub fn time_block(&self) -> Option<(NaiveTime, NaiveTime)> {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) if start < end => Some((start, end)),
+            _ => None,
+        }
+    }
+}
+
+/// A change to some fields of a task. Fields left at `None` keep their value; optional fields
+/// are cleared with `Some(None)`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaskPatch {
+    pub id: Id,
+    /// The version of the task the change was made to. When set, the patch is refused if the
+    /// task was written since, instead of overwriting what someone else did.
+    pub if_match: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub important: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub urgent: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub content: Option<Option<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub role_id: Option<Option<Id>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub backlog_id: Option<Option<Id>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub scheduled_date: Option<Option<NaiveDate>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub assignee_id: Option<Option<Id>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub estimate_minutes: Option<Option<i32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub start_time: Option<Option<NaiveTime>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub end_time: Option<Option<NaiveTime>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub recurrence: Option<Option<Recurrence>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub project_id: Option<Option<Id>>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "present")]
+    pub status_id: Option<Option<Id>>,
+}
+
+/// Keeps a field that is there but `null` apart from one that was left out.
+fn present<'de, T: Deserialize<'de>, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl TaskPatch {
+    /// An empty patch to the version of `task` the client has.
+    pub fn new(task: &Task) -> Self {
+        Self {
+            id: task.id,
+            if_match: Some(task.version),
+            title: None,
+            important: None,
+            urgent: None,
+            content: None,
+            completed: None,
+            role_id: None,
+            backlog_id: None,
+            scheduled_date: None,
+            assignee_id: None,
+            estimate_minutes: None,
+            start_time: None,
+            end_time: None,
+            recurrence: None,
+            project_id: None,
+            status_id: None,
+        }
+    }
+
+    /// `task` with the patched fields changed.
+    pub fn apply(&self, task: &Task) -> Task {
+        let task = task.clone();
+        Task {
+            title: self.title.clone().unwrap_or(task.title),
+            important: self.important.unwrap_or(task.important),
+            urgent: self.urgent.unwrap_or(task.urgent),
+            content: self.content.clone().unwrap_or(task.content),
+            completed: self.completed.unwrap_or(task.completed),
+            role_id: self.role_id.unwrap_or(task.role_id),
+            backlog_id: self.backlog_id.unwrap_or(task.backlog_id),
+            scheduled_date: self.scheduled_date.unwrap_or(task.scheduled_date),
+            assignee_id: self.assignee_id.unwrap_or(task.assignee_id),
+            estimate_minutes: self.estimate_minutes.unwrap_or(task.estimate_minutes),
+            start_time: self.start_time.unwrap_or(task.start_time),
+            end_time: self.end_time.unwrap_or(task.end_time),
+            recurrence: self.recurrence.unwrap_or(task.recurrence),
+            project_id: self.project_id.unwrap_or(task.project_id),
+            status_id: self.status_id.unwrap_or(task.status_id),
+            ..task
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub enum Recurrence {
+    Daily,
+    Weekly(Weekday),
+}
+
+impl Recurrence {
+    /// The first day after `day` the task recurs on.
+    pub fn next_after(&self, day: NaiveDate) -> NaiveDate {
+        let next = day.succ_opt().unwrap_or(day);
+        match self {
+            Recurrence::Daily => next,
+            Recurrence::Weekly(weekday) => next_weekday(next, *weekday),
+        }
+    }
+}
+
+/// The first `weekday` on or after `day`.
+pub fn next_weekday(day: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let ahead = (7 + weekday.num_days_from_monday() - day.weekday().num_days_from_monday()) % 7;
+    day.checked_add_days(Days::new(ahead as u64)).unwrap_or(day)
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => f.write_str("daily"),
+            Recurrence::Weekly(weekday) => write!(f, "weekly:{}", weekday),
+        }
+    }
+}
+
+impl std::str::FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "daily" => Ok(Recurrence::Daily),
+            Some(("weekly", weekday)) => weekday
+                .parse()
+                .map(Recurrence::Weekly)
+                .map_err(|_| format!("Unknown weekday in recurrence: {}", s)),
+            _ => Err(format!("Unknown recurrence: {}", s)),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for Recurrence {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.to_string());
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for Recurrence {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(s.parse()?)
+    }
+}
+
+/// A task typed into a list with inline tokens, as understood before it is created.
+/// Backlog and role are still names here; the server looks them up.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct QuickAdd {
+    pub title: String,
     pub scheduled_date: Option<NaiveDate>,
-    pub backlog_id: Option<Id>,
+    pub backlog: Option<String>,
+    pub role: Option<String>,
+    pub important: bool,
+    pub urgent: bool,
+    pub estimate_minutes: Option<i32>,
+    pub recurrence: Option<Recurrence>,
+}
+
+impl QuickAdd {
+    /// A plain title without any tokens.
+    pub fn title(title: impl Into<String>) -> Self {
+        QuickAdd { title: title.into(), ..Default::default() }
+    }
+}
+
+/// Ids of time-blocked tasks that overlap another block on the same day.
+pub fn overlapping_tasks(tasks: &[Task]) -> Vec<Id> {
+    let blocks: Vec<(&Task, NaiveDate, NaiveTime, NaiveTime)> = tasks
+        .iter()
+        .filter_map(|t| {
+            let (start, end) = t.time_block()?;
+            Some((t, t.scheduled_date?, start, end))
+        })
+        .collect();
+
+    blocks
+        .iter()
+        .filter(|(task, day, start, end)| {
+            blocks.iter().any(|(other, other_day, other_start, other_end)| {
+                other.id != task.id && other_day == day && start < other_end && other_start < end
+            })
+        })
+        .map(|(task, ..)| task.id)
+        .collect()
+}
+
+/// Which scheduled dates a filter matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateFilter {
+    /// Tasks without a scheduled date.
+    #[default]
+    Unscheduled,
+    On(NaiveDate),
+    /// Scheduled between the two dates, inclusive. A missing bound is open-ended.
+    Between(Option<NaiveDate>, Option<NaiveDate>),
+    Any,
+}
+
+/// Which backlog a filter matches.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BacklogFilter {
+    /// Tasks outside any backlog.
+    #[default]
+    None,
+    In(Id),
+    /// Tasks in any backlog visible to the user, or none.
+    Any,
+}
+
+/// Order in which filtered tasks are returned. Ties fall back to creation order.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TaskSort {
+    /// Oldest first.
+    #[default]
+    Created,
+    /// Earliest date first, unscheduled tasks before all others.
+    Scheduled,
+    Title,
+    /// Most recently changed first.
+    Updated,
+    /// Important before urgent before the rest.
+    Priority,
+}
+
+impl TaskSort {
+    pub const ALL: [TaskSort; 5] = [
+        TaskSort::Created,
+        TaskSort::Scheduled,
+        TaskSort::Title,
+        TaskSort::Updated,
+        TaskSort::Priority,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskSort::Created => "Created",
+            TaskSort::Scheduled => "Date",
+            TaskSort::Title => "Title",
+            TaskSort::Updated => "Recently changed",
+            TaskSort::Priority => "Priority",
+        }
+    }
+}
+
+/// Stored as JSON when saved as part of a view.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub struct TaskFilter {
+    pub scheduled: DateFilter,
+    pub backlog: BacklogFilter,
+    /// Only return tasks assigned to the user making the request.
+    pub assigned_to_me: bool,
+    pub completed: Option<bool>,
+    pub important: Option<bool>,
+    pub urgent: Option<bool>,
+    /// Only tasks last changed on or after this date.
+    pub updated_since: Option<NaiveDate>,
+    /// Only tasks completed on or after this date.
+    #[serde(default)]
+    pub completed_since: Option<NaiveDate>,
+    pub role_id: Option<Id>,
+    /// Case-insensitive match against title and content.
+    pub text: Option<String>,
+    pub sort: TaskSort,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// Only tasks carrying all of these tags.
+    #[serde(default)]
+    pub tags: Vec<Id>,
+    /// Archived tasks instead of the ones in use.
+    #[serde(default)]
+    pub archived: bool,
+}
+
+impl TaskFilter {
+    /// Everything scheduled on `day`, whether in the inbox or in a backlog the user can see.
+    pub fn day(day: NaiveDate) -> Self {
+        TaskFilter { scheduled: DateFilter::On(day), backlog: BacklogFilter::Any, ..Default::default() }
+    }
+
+    pub fn backlog(backlog_id: Id) -> Self {
+        TaskFilter { backlog: BacklogFilter::In(backlog_id), ..Default::default() }
+    }
+
+    /// The date and backlog a task created in, or dropped on, a list with this filter gets.
+    /// `None` when the filter doesn't describe a single place a task can be put.
+    ///
+    /// Lists of a day show tasks of any backlog, but tasks created there go to the inbox.
+    pub fn target(&self) -> Option<(Option<NaiveDate>, Option<Id>)> {
+        let date = match self.scheduled {
+            DateFilter::Unscheduled => None,
+            DateFilter::On(day) => Some(day),
+            _ => return None,
+        };
+        let backlog = match self.backlog {
+            BacklogFilter::None => None,
+            BacklogFilter::In(id) => Some(id),
+            BacklogFilter::Any if date.is_some() => None,
+            BacklogFilter::Any => return None,
+        };
+        Some((date, backlog))
+    }
+
+    /// Whether a task belongs in a list with this filter, as far as can be told on the client.
+    /// Assignment and backlog visibility depend on the user and tags aren't part of a `Task`,
+    /// so those are left to the server.
+    pub fn matches(&self, task: &Task) -> bool {
+        let scheduled = match (self.scheduled, task.scheduled_date) {
+            (DateFilter::Unscheduled, date) => date.is_none(),
+            (DateFilter::On(day), date) => date == Some(day),
+            (DateFilter::Between(from, to), Some(date)) => from.is_none_or(|f| date >= f) && to.is_none_or(|t| date <= t),
+            (DateFilter::Between(..), None) => false,
+            (DateFilter::Any, _) => true,
+        };
+        let backlog = match self.backlog {
+            BacklogFilter::None => task.backlog_id.is_none(),
+            BacklogFilter::In(id) => task.backlog_id == Some(id),
+            BacklogFilter::Any => true,
+        };
+        let updated = match self.updated_since {
+            Some(since) => task.updated_at.is_some_and(|u| u.date() >= since),
+            None => true,
+        };
+        let completed_since = match self.completed_since {
+            Some(since) => task.completed_at.is_some_and(|c| c.date() >= since),
+            None => true,
+        };
+        let text = match self.text.as_deref().map(str::trim) {
+            Some(text) if !text.is_empty() => {
+                let text = text.to_lowercase();
+                task.title.to_lowercase().contains(&text)
+                    || task.content.as_ref().is_some_and(|c| c.to_lowercase().contains(&text))
+            },
+            _ => true,
+        };
+
+        scheduled
+            && backlog
+            && updated
+            && completed_since
+            && text
+            && self.role_id.is_none_or(|r| task.role_id == Some(r))
+            && self.completed.is_none_or(|c| task.completed == c)
+            && self.important.is_none_or(|i| task.important == i)
+            && self.urgent.is_none_or(|u| task.urgent == u)
+            && task.archived_at.is_some() == self.archived
+    }
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for TaskFilter {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(serde_json::to_string(self)?);
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for TaskFilter {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+/// The stored preferences of a user; users without a row get the defaults.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = user_preferences))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct UserPreferences {
+    pub user_id: Id,
+    pub preferences: Preferences,
+}
+
+/// A named filter saved by a user and shown next to the smart lists.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = saved_views))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct SavedView {
+    pub id: Id,
+    pub owner_id: Id,
+    pub name: String,
+    pub filter: TaskFilter,
+    /// When set, the view covers this many days starting today instead of the filter's dates,
+    /// so "next 14 days" keeps moving along.
+    pub upcoming_days: Option<i32>,
+    /// The view as written in the task query language. When set it is parsed again on every
+    /// use, so relative dates like `due<+7d` keep moving along, and `filter` is a fallback.
+    pub query: Option<String>,
+}
+
+impl SavedView {
+    /// The filter to fetch the view's tasks with on `today`.
+    pub fn filter_on(&self, today: NaiveDate) -> TaskFilter {
+        match self.upcoming_days {
+            Some(days) => TaskFilter {
+                scheduled: DateFilter::Between(
+                    Some(today),
+                    today.checked_add_days(Days::new(days.max(1) as u64 - 1)),
+                ),
+                ..self.filter.clone()
+            },
+            None => self.filter.clone(),
+        }
+    }
+}
+
+/// Built-in views across all tasks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartList {
+    Today,
+    Overdue,
+    Next7Days,
+    Important,
+    CompletedThisWeek,
+    Archived,
+}
+
+impl SmartList {
+    pub const ALL: [SmartList; 6] = [
+        SmartList::Today,
+        SmartList::Overdue,
+        SmartList::Next7Days,
+        SmartList::Important,
+        SmartList::CompletedThisWeek,
+        SmartList::Archived,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SmartList::Today => "Today",
+            SmartList::Overdue => "Overdue",
+            SmartList::Next7Days => "Next 7 days",
+            SmartList::Important => "Important",
+            SmartList::CompletedThisWeek => "Completed this week",
+            SmartList::Archived => "Archived",
+        }
+    }
+
+    /// The filter of the list on `today`; weeks start as set in `preferences`.
+    pub fn filter(&self, today: NaiveDate, preferences: &Preferences) -> TaskFilter {
+        let any = TaskFilter {
+            scheduled: DateFilter::Any,
+            backlog: BacklogFilter::Any,
+            ..Default::default()
+        };
+
+        match self {
+            SmartList::Today => TaskFilter { scheduled: DateFilter::On(today), ..any },
+            SmartList::Overdue => TaskFilter {
+                scheduled: DateFilter::Between(None, today.pred_opt()),
+                completed: Some(false),
+                ..any
+            },
+            SmartList::Next7Days => TaskFilter {
+                scheduled: DateFilter::Between(Some(today), today.checked_add_days(Days::new(6))),
+                completed: Some(false),
+                ..any
+            },
+            SmartList::Important => TaskFilter {
+                important: Some(true),
+                completed: Some(false),
+                ..any
+            },
+            SmartList::CompletedThisWeek => TaskFilter {
+                completed: Some(true),
+                completed_since: Some(preferences.week_of(today)),
+                ..any
+            },
+            SmartList::Archived => TaskFilter { archived: true, sort: TaskSort::Updated, ..any },
+        }
+    }
 }
 
 #[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
@@ -70,6 +589,165 @@ pub struct TaskFilter {
 pub struct Backlog {
     pub id: Id,
     pub name: String,
+    pub project_id: Option<Id>,
+    /// Order among the backlogs, lowest first.
+    pub position: i32,
+    /// Archived backlogs keep their tasks but are hidden from the board and from searches.
+    pub archived_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+/// What happens to a backlog and its tasks when it is removed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacklogRemoval {
+    /// Moves the tasks to another backlog, or to the inbox with `None`, then deletes the backlog.
+    MoveTasks(Option<Id>),
+    /// Deletes the backlog together with its tasks.
+    DeleteTasks,
+    /// Hides the backlog and its tasks until it is restored.
+    Archive,
+}
+
+/// A status column on a backlog's board, such as To do, Doing or Done.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = backlog_columns))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct BacklogColumn {
+    pub id: Id,
+    pub backlog_id: Id,
+    pub name: String,
+    /// Order on the board, lowest first.
+    pub position: i32,
+    /// How many tasks the column should hold at most. Going over only warns.
+    pub wip_limit: Option<i32>,
+}
+
+impl BacklogColumn {
+    pub fn over_limit(&self, count: usize) -> bool {
+        self.wip_limit.is_some_and(|limit| count > limit.max(0) as usize)
+    }
+}
+
+/// Columns a board starts out with when they are added in one go.
+pub const DEFAULT_COLUMNS: [&str; 3] = ["To do", "Doing", "Done"];
+
+/// The column a task is shown in: its own if that is still on the board, otherwise the first.
+pub fn column_of(task: &Task, columns: &[BacklogColumn]) -> Option<Id> {
+    match task.status_id {
+        Some(status) if columns.iter().any(|c| c.id == status) => Some(status),
+        _ => columns.first().map(|c| c.id),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub enum ProjectStatus {
+    #[default]
+    Planned,
+    Active,
+    OnHold,
+    Done,
+}
+
+impl ProjectStatus {
+    pub const ALL: [ProjectStatus; 4] = [
+        ProjectStatus::Planned,
+        ProjectStatus::Active,
+        ProjectStatus::OnHold,
+        ProjectStatus::Done,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Planned => "planned",
+            ProjectStatus::Active => "active",
+            ProjectStatus::OnHold => "on-hold",
+            ProjectStatus::Done => "done",
+        }
+    }
+}
+
+impl std::fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ProjectStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "planned" => Ok(ProjectStatus::Planned),
+            "active" => Ok(ProjectStatus::Active),
+            "on-hold" => Ok(ProjectStatus::OnHold),
+            "done" => Ok(ProjectStatus::Done),
+            other => Err(format!("Unknown project status: {}", other)),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for ProjectStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.as_str());
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for ProjectStatus {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(s.parse()?)
+    }
+}
+
+/// A body of work spanning backlogs and tasks, tracked against milestones.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = projects))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct Project {
+    pub id: Id,
+    pub name: String,
+    pub description: Option<String>,
+    pub status: ProjectStatus,
+    pub target_date: Option<NaiveDate>,
+    /// Who created the project and may change it and its milestones.
+    pub owner_id: Option<Id>,
+}
+
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = milestones))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct Milestone {
+    pub id: Id,
+    pub project_id: Id,
+    pub name: String,
+    pub due_date: Option<NaiveDate>,
+    /// Order within the project, lowest first.
+    pub position: i32,
+    pub completed: bool,
+}
+
+/// Open tasks of a project at the end of a day.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BurndownPoint {
+    pub date: NaiveDate,
+    pub remaining: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ProjectOverview {
+    pub project: Project,
+    pub milestones: Vec<Milestone>,
+    pub total_tasks: i32,
+    pub completed_tasks: i32,
+    pub burndown: Vec<BurndownPoint>,
 }
 
 #[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
@@ -80,3 +758,575 @@ pub struct Role {
     pub id: Id,
     pub name: String,
 }
+
+/// A label that can be put on any number of tasks, across backlogs and roles.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = tags))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct Tag {
+    pub id: Id,
+    pub name: String,
+    /// CSS color, e.g. `#f59e0b`.
+    pub color: String,
+}
+
+impl Tag {
+    /// Tags are told apart by name regardless of case and surrounding spaces, so `Home` and
+    /// `home ` are the same tag.
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.trim().eq_ignore_ascii_case(name.trim())
+    }
+}
+
+/// Colors handed out to new tags in turn.
+pub const TAG_COLORS: [&str; 8] = [
+    "#ef4444", "#f59e0b", "#10b981", "#3b82f6", "#8b5cf6", "#ec4899", "#14b8a6", "#84cc16",
+];
+
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = task_tags))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct TaskTag {
+    pub task_id: Id,
+    pub tag_id: Id,
+}
+
+/// `blocker_id` has to be completed before `blocked_id` can be worked on.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = task_dependencies))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct TaskDependency {
+    pub blocker_id: Id,
+    pub blocked_id: Id,
+}
+
+/// The tasks a task waits for and the tasks waiting for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskLinks {
+    pub blocked_by: Vec<Task>,
+    pub blocks: Vec<Task>,
+}
+
+impl TaskLinks {
+    /// Whether any task this one waits for is still open.
+    pub fn is_blocked(&self) -> bool {
+        self.blocked_by.iter().any(|t| !t.completed)
+    }
+}
+
+/// Warnings for a task scheduled before a task it waits for, or after a task waiting for it.
+pub fn schedule_warnings(task: &Task, links: &TaskLinks) -> Vec<String> {
+    let Some(day) = task.scheduled_date else {
+        return vec![];
+    };
+
+    let mut warnings = vec![];
+    for blocker in links.blocked_by.iter().filter(|t| !t.completed) {
+        if blocker.scheduled_date.is_some_and(|d| day < d) {
+            warnings.push(format!("\"{}\" is scheduled before its blocker \"{}\"", task.title, blocker.title));
+        }
+    }
+    for dependent in links.blocks.iter().filter(|t| !t.completed) {
+        if dependent.scheduled_date.is_some_and(|d| d < day) {
+            warnings.push(format!("\"{}\" is scheduled after \"{}\", which waits for it", task.title, dependent.title));
+        }
+    }
+    warnings
+}
+
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = users))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct User {
+    pub id: Id,
+    pub name: String,
+}
+
+/// A signed-in browser. The token is kept in a cookie and identifies the user on every request.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = sessions))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct Session {
+    pub token: String,
+    pub user_id: Id,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Access level of a user on a shared backlog, ordered from least to most privileged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub enum MemberRole {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl MemberRole {
+    pub const ALL: [MemberRole; 3] = [MemberRole::Viewer, MemberRole::Editor, MemberRole::Owner];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemberRole::Viewer => "viewer",
+            MemberRole::Editor => "editor",
+            MemberRole::Owner => "owner",
+        }
+    }
+}
+
+impl std::fmt::Display for MemberRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for MemberRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(MemberRole::Viewer),
+            "editor" => Ok(MemberRole::Editor),
+            "owner" => Ok(MemberRole::Owner),
+            other => Err(format!("Unknown member role: {}", other)),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for MemberRole {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.as_str());
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for MemberRole {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(s.parse()?)
+    }
+}
+
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = backlog_members))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct BacklogMember {
+    pub backlog_id: Id,
+    pub user_id: Id,
+    pub role: MemberRole,
+}
+
+/// A backlog member together with the user it refers to, as shown in the UI.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub user: User,
+    pub role: MemberRole,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+    Reverted,
+}
+
+impl ChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "updated",
+            ChangeKind::Deleted => "deleted",
+            ChangeKind::Reverted => "reverted",
+        }
+    }
+}
+
+impl std::fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ChangeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(ChangeKind::Created),
+            "updated" => Ok(ChangeKind::Updated),
+            "deleted" => Ok(ChangeKind::Deleted),
+            "reverted" => Ok(ChangeKind::Reverted),
+            other => Err(format!("Unknown change kind: {}", other)),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for ChangeKind {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(self.as_str());
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for ChangeKind {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(s.parse()?)
+    }
+}
+
+/// Value of a single task field before and after a change, in its serialized form.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+/// The field changes of one mutation, stored as a JSON array.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub struct FieldChanges(pub Vec<FieldChange>);
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for FieldChanges {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(serde_json::to_string(&self.0)?);
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for FieldChanges {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(FieldChanges(serde_json::from_str(s)?))
+    }
+}
+
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = task_changes))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct TaskChange {
+    pub id: Id,
+    pub task_id: Id,
+    pub actor_id: Option<Id>,
+    pub kind: ChangeKind,
+    pub changes: FieldChanges,
+    pub changed_at: NaiveDateTime,
+    /// Set while the change is undone, cleared again when it is redone.
+    pub undone_at: Option<NaiveDateTime>,
+    /// Shared by all changes made by one action, which undo and redo treat as one.
+    pub batch_id: Id,
+}
+
+/// A task change with the name of the user that made it, for the history timeline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TaskHistoryEntry {
+    pub change: TaskChange,
+    pub actor: Option<String>,
+}
+
+/// Available working time of a user, either for every occurrence of a weekday or for one
+/// specific date. A date entry overrides the weekday entry for that day.
+#[cfg_attr(feature = "server", derive(Queryable, Insertable, Selectable))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", diesel(table_name = capacities))]
+#[cfg_attr(feature = "server", diesel(check_for_backend(diesel::sqlite::Sqlite)))]
+pub struct Capacity {
+    pub id: Id,
+    pub user_id: Id,
+    /// Days from Monday, 0 to 6.
+    pub weekday: Option<i32>,
+    pub date: Option<NaiveDate>,
+    pub minutes: i32,
+}
+
+/// Capacity used for days without any configured capacity.
+pub const DEFAULT_CAPACITY_MINUTES: i32 = 8 * 60;
+
+/// Capacity in minutes available on `day`.
+pub fn capacity_on(capacities: &[Capacity], day: NaiveDate) -> i32 {
+    let weekday = day.weekday().num_days_from_monday() as i32;
+    capacities
+        .iter()
+        .find(|c| c.date == Some(day))
+        .or_else(|| capacities.iter().find(|c| c.weekday == Some(weekday)))
+        .map(|c| c.minutes)
+        .unwrap_or(DEFAULT_CAPACITY_MINUTES)
+}
+
+/// Minutes of estimated work in a set of tasks. Completed tasks no longer take up time.
+pub fn planned_minutes(tasks: &[Task]) -> i32 {
+    tasks
+        .iter()
+        .filter(|t| !t.completed)
+        .filter_map(|t| t.estimate_minutes)
+        .sum()
+}
+
+/// Formats a number of minutes as e.g. `1h30`, `45m` or `2h`.
+pub fn format_minutes(minutes: i32) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{:02}", h, m),
+    }
+}
+
+/// Proposal to move a task from an overloaded day to a lighter one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RebalanceSuggestion {
+    pub task: Task,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// What happens to open tasks left on a past day.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RolloverPolicy {
+    #[default]
+    MoveToToday,
+    /// Keep the original on its day and schedule a copy today.
+    CopyToToday,
+    MoveToInbox,
+}
+
+impl std::str::FromStr for RolloverPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "move-to-today" => Ok(RolloverPolicy::MoveToToday),
+            "copy-to-today" => Ok(RolloverPolicy::CopyToToday),
+            "move-to-inbox" => Ok(RolloverPolicy::MoveToInbox),
+            other => Err(format!("Unknown rollover policy: {}", other)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RolloverReport {
+    pub policy: RolloverPolicy,
+    pub rolled_over: usize,
+}
+
+/// Tasks pointing at a role or backlog that no longer exists.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntegrityReport {
+    pub dangling_roles: Vec<Id>,
+    pub dangling_backlogs: Vec<Id>,
+    /// Whether the dangling references were cleared.
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.dangling_roles.is_empty() && self.dangling_backlogs.is_empty()
+    }
+}
+
+impl std::fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_clean() {
+            return f.write_str("No dangling references");
+        }
+        let verb = if self.repaired { "cleared" } else { "found" };
+        write!(
+            f,
+            "{} {} task(s) with a missing role and {} with a missing backlog",
+            verb,
+            self.dangling_roles.len(),
+            self.dangling_backlogs.len(),
+        )
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A day in March 2025.
+    pub(crate) fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    pub(crate) fn id() -> Id {
+        Id(Uuid::now_v7())
+    }
+
+    /// An open, unscheduled inbox task.
+    pub(crate) fn task(title: &str) -> Task {
+        Task {
+            id: id(),
+            title: title.to_string(),
+            important: false,
+            urgent: false,
+            content: None,
+            completed: false,
+            role_id: None,
+            backlog_id: None,
+            scheduled_date: None,
+            created_at: date(1).and_hms_opt(9, 0, 0).unwrap(),
+            updated_at: None,
+            deleted_at: None,
+            assignee_id: None,
+            estimate_minutes: None,
+            start_time: None,
+            end_time: None,
+            postponed_count: 0,
+            rolled_over_at: None,
+            recurrence: None,
+            project_id: None,
+            status_id: None,
+            version: 0,
+            completed_at: None,
+            archived_at: None,
+            owner_id: None,
+        }
+    }
+
+    #[test]
+    fn day_lists_match_tasks_of_any_backlog() {
+        let backlog = id();
+        let filter = TaskFilter::day(date(10));
+        assert!(filter.matches(&Task { scheduled_date: Some(date(10)), ..task("inbox") }));
+        assert!(filter.matches(&Task { scheduled_date: Some(date(10)), backlog_id: Some(backlog), ..task("backlog") }));
+        assert!(!filter.matches(&Task { scheduled_date: Some(date(11)), ..task("next day") }));
+        assert!(!filter.matches(&task("unscheduled")));
+    }
+
+    #[test]
+    fn date_filters() {
+        let on = Task { scheduled_date: Some(date(10)), ..task("on the 10th") };
+        let unscheduled = task("unscheduled");
+        let filter = |scheduled| TaskFilter { scheduled, backlog: BacklogFilter::Any, ..Default::default() };
+
+        assert!(filter(DateFilter::Unscheduled).matches(&unscheduled));
+        assert!(!filter(DateFilter::Unscheduled).matches(&on));
+        assert!(filter(DateFilter::Any).matches(&unscheduled));
+        assert!(filter(DateFilter::Any).matches(&on));
+        assert!(filter(DateFilter::Between(Some(date(10)), Some(date(10)))).matches(&on));
+        assert!(filter(DateFilter::Between(None, Some(date(10)))).matches(&on));
+        assert!(filter(DateFilter::Between(Some(date(10)), None)).matches(&on));
+        assert!(!filter(DateFilter::Between(Some(date(11)), None)).matches(&on));
+        assert!(!filter(DateFilter::Between(None, Some(date(9)))).matches(&on));
+        assert!(!filter(DateFilter::Between(None, None)).matches(&unscheduled));
+    }
+
+    #[test]
+    fn backlog_filters() {
+        let backlog = id();
+        let inbox = task("inbox");
+        let in_backlog = Task { backlog_id: Some(backlog), ..task("in backlog") };
+        let elsewhere = Task { backlog_id: Some(id()), ..task("other backlog") };
+
+        let none = TaskFilter::default();
+        assert!(none.matches(&inbox));
+        assert!(!none.matches(&in_backlog));
+
+        let one = TaskFilter::backlog(backlog);
+        assert!(one.matches(&in_backlog));
+        assert!(!one.matches(&inbox));
+        assert!(!one.matches(&elsewhere));
+
+        let any = TaskFilter { backlog: BacklogFilter::Any, ..Default::default() };
+        assert!([&inbox, &in_backlog, &elsewhere].iter().all(|task| any.matches(task)));
+    }
+
+    #[test]
+    fn completed_and_flag_filters() {
+        let done = Task { completed: true, completed_at: Some(date(5).and_hms_opt(17, 0, 0).unwrap()), ..task("done") };
+        let open = Task { important: true, urgent: true, ..task("open") };
+
+        let completed = TaskFilter { completed: Some(true), ..Default::default() };
+        assert!(completed.matches(&done));
+        assert!(!completed.matches(&open));
+
+        let since = |day| TaskFilter { completed_since: Some(date(day)), ..Default::default() };
+        assert!(since(5).matches(&done));
+        assert!(!since(6).matches(&done));
+        assert!(!since(1).matches(&open));
+
+        let important = TaskFilter { important: Some(true), ..Default::default() };
+        let not_urgent = TaskFilter { urgent: Some(false), ..Default::default() };
+        assert!(important.matches(&open));
+        assert!(!important.matches(&done));
+        assert!(!not_urgent.matches(&open));
+        assert!(not_urgent.matches(&done));
+    }
+
+    #[test]
+    fn text_role_and_update_filters() {
+        let role = id();
+        let matching = Task {
+            content: Some("Call the Bank".to_string()),
+            role_id: Some(role),
+            updated_at: Some(date(8).and_hms_opt(12, 0, 0).unwrap()),
+            ..task("Finances")
+        };
+        let text = |text: &str| TaskFilter { text: Some(text.to_string()), ..Default::default() };
+        assert!(text("bank").matches(&matching));
+        assert!(text("FINANCE").matches(&matching));
+        assert!(text("  ").matches(&matching));
+        assert!(!text("groceries").matches(&matching));
+
+        assert!(TaskFilter { role_id: Some(role), ..Default::default() }.matches(&matching));
+        assert!(!TaskFilter { role_id: Some(id()), ..Default::default() }.matches(&matching));
+
+        assert!(TaskFilter { updated_since: Some(date(8)), ..Default::default() }.matches(&matching));
+        assert!(!TaskFilter { updated_since: Some(date(9)), ..Default::default() }.matches(&matching));
+        assert!(!TaskFilter { updated_since: Some(date(1)), ..Default::default() }.matches(&task("never updated")));
+    }
+
+    #[test]
+    fn archived_tasks_only_match_archive_filters() {
+        let archived = Task { completed: true, archived_at: Some(date(9).and_hms_opt(8, 0, 0).unwrap()), ..task("archived") };
+        let current = task("current");
+        let archive = TaskFilter { archived: true, ..Default::default() };
+
+        assert!(archive.matches(&archived));
+        assert!(!archive.matches(&current));
+        assert!(!TaskFilter::default().matches(&archived));
+        assert!(TaskFilter::default().matches(&current));
+    }
+
+    #[test]
+    fn targets() {
+        let backlog = id();
+        assert_eq!(TaskFilter::default().target(), Some((None, None)));
+        assert_eq!(TaskFilter::backlog(backlog).target(), Some((None, Some(backlog))));
+        assert_eq!(TaskFilter::day(date(10)).target(), Some((Some(date(10)), None)));
+        assert_eq!(
+            TaskFilter { scheduled: DateFilter::On(date(10)), backlog: BacklogFilter::In(backlog), ..Default::default() }.target(),
+            Some((Some(date(10)), Some(backlog))),
+        );
+        assert_eq!(TaskFilter { backlog: BacklogFilter::Any, ..Default::default() }.target(), None);
+        assert_eq!(TaskFilter { scheduled: DateFilter::Any, ..Default::default() }.target(), None);
+        assert_eq!(TaskFilter { scheduled: DateFilter::Between(Some(date(1)), Some(date(7))), ..Default::default() }.target(), None);
+    }
+
+    #[test]
+    fn tag_names_ignore_case_and_spaces() {
+        let tag = Tag { id: id(), name: "Home".to_string(), color: TAG_COLORS[0].to_string() };
+        assert!(tag.is_named("home"));
+        assert!(tag.is_named(" HOME "));
+        assert!(!tag.is_named("homework"));
+    }
+}
@@ -0,0 +1,268 @@
+use dioxus::prelude::*;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use chrono::Days;
+use http::HeaderMap;
+use http::header::{COOKIE, HeaderValue, SET_COOKIE};
+use std::env;
+use uuid::Uuid;
+use super::model::*;
+use super::preferences::utc_now;
+use super::server::DbConnection;
+
+/// Cookie holding the session token of a signed-in browser.
+const SESSION_COOKIE: &str = "planner_session";
+/// How long a session stays valid after it was created.
+const SESSION_DAYS: u64 = 90;
+
+/// The session token sent with the current request, if any.
+pub async fn request_session_token() -> Option<String> {
+    let headers = extract::<HeaderMap, _>().await.ok()?;
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// The user to act as outside of a request, from `PLANNER_USER` or `local` when unset.
+pub fn configured_user_name() -> String {
+    env::var("PLANNER_USER").unwrap_or_else(|_| "local".to_string())
+}
+
+/// Starts a session for `user` and returns it; its token is what the browser signs in with.
+pub async fn create_session(conn: &mut DbConnection, user: &User) -> Result<Session, ServerFnError> {
+    use super::schema::sessions;
+
+    let now = utc_now();
+    // Two random UUIDs give 244 bits of randomness, too many to guess
+    let session = Session {
+        token: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+        user_id: user.id,
+        created_at: now,
+        expires_at: now.checked_add_days(Days::new(SESSION_DAYS)).unwrap_or(now),
+    };
+    diesel::insert_into(sessions::table)
+        .values(&session)
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    Ok(session)
+}
+
+/// The user of an unexpired session.
+pub async fn session_user(conn: &mut DbConnection, token: &str) -> Result<Option<User>, ServerFnError> {
+    use super::schema::{sessions, users};
+
+    users::table
+        .inner_join(sessions::table)
+        .filter(sessions::token.eq(token))
+        .filter(sessions::expires_at.gt(utc_now()))
+        .select(User::as_select())
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+pub async fn delete_session(conn: &mut DbConnection, token: &str) -> Result<(), ServerFnError> {
+    use super::schema::sessions;
+
+    diesel::delete(sessions::table.filter(sessions::token.eq(token)))
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
+    Ok(())
+}
+
+/// Makes the browser send `token` with every request until it expires, or forget it when empty.
+pub fn set_session_cookie(token: &str) -> Result<(), ServerFnError> {
+    let max_age = if token.is_empty() { 0 } else { SESSION_DAYS * 24 * 60 * 60 };
+    let cookie = format!("{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Strict", SESSION_COOKIE, token, max_age);
+    let value = HeaderValue::from_str(&cookie).map_err(|e| ServerFnError::new(format!("Invalid cookie: {}", e)))?;
+    server_context().response_parts_mut().headers.append(SET_COOKIE, value);
+    Ok(())
+}
+
+/// Looks up a user by name, creating it if this is the first time we see it.
+pub async fn find_or_create_user(conn: &mut DbConnection, user_name: &str) -> Result<User, ServerFnError> {
+    use super::schema::users::dsl::*;
+
+    let existing = users
+        .filter(name.eq(user_name))
+        .select(User::as_select())
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    if let Some(user) = existing {
+        return Ok(user);
+    }
+
+    let first = users
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?
+        == 0;
+
+    let user = User {
+        id: Id(Uuid::now_v7()),
+        name: user_name.to_string(),
+    };
+    diesel::insert_into(users)
+        .values(&user)
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    // Tasks and projects made before anyone signed in belong to the first user, like they did
+    // before there were several
+    if first {
+        use super::schema::{projects, tasks};
+
+        diesel::update(tasks::table.filter(tasks::owner_id.is_null()))
+            .set(tasks::owner_id.eq(user.id))
+            .execute(conn)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
+        diesel::update(projects::table.filter(projects::owner_id.is_null()))
+            .set(projects::owner_id.eq(user.id))
+            .execute(conn)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
+    }
+
+    Ok(user)
+}
+
+/// The user the current request is made on behalf of, as identified by its session.
+///
+/// Requests without a valid session are refused, unless `PLANNER_USER` is set: then they act as
+/// that user, for installs only their one user can reach.
+pub async fn current_user(conn: &mut DbConnection) -> Result<User, ServerFnError> {
+    if let Some(token) = request_session_token().await {
+        if let Some(user) = session_user(conn, &token).await? {
+            return Ok(user);
+        }
+    }
+    match env::var("PLANNER_USER") {
+        Ok(user_name) if !user_name.trim().is_empty() => find_or_create_user(conn, user_name.trim()).await,
+        _ => Err(ServerFnError::new(NOT_SIGNED_IN)),
+    }
+}
+
+/// Error of requests made without signing in.
+pub const NOT_SIGNED_IN: &str = "Not signed in";
+
+/// Role of `user` on a backlog. Every backlog is owned by its creator from the start, so users
+/// who aren't members have no access.
+pub async fn backlog_role(conn: &mut DbConnection, backlog: Id, user: &User) -> Result<Option<MemberRole>, ServerFnError> {
+    use super::schema::backlog_members::dsl::*;
+
+    let members = backlog_members
+        .filter(backlog_id.eq(backlog))
+        .select(BacklogMember::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(members.into_iter().find(|m| m.user_id == user.id).map(|m| m.role))
+}
+
+pub async fn require_backlog_role(conn: &mut DbConnection, backlog: Id, user: &User, needed: MemberRole) -> Result<(), ServerFnError> {
+    match backlog_role(conn, backlog, user).await? {
+        Some(role) if role >= needed => Ok(()),
+        _ => Err(ServerFnError::new(format!("Permission denied: {} access to backlog required", needed))),
+    }
+}
+
+/// Backlog tasks follow their backlog; tasks outside one are only open to their owner.
+pub async fn require_task_role(conn: &mut DbConnection, task: &Task, user: &User, needed: MemberRole) -> Result<(), ServerFnError> {
+    match task.backlog_id {
+        Some(backlog) => require_backlog_role(conn, backlog, user, needed).await,
+        None if task.owner_id == Some(user.id) => Ok(()),
+        None => Err(ServerFnError::new("Permission denied: task belongs to another user")),
+    }
+}
+
+/// Ids of the backlogs `user` can see: the ones they are a member of.
+/// Deleted backlogs are left out, archived ones are included.
+pub async fn visible_backlog_ids(conn: &mut DbConnection, user: &User) -> Result<Vec<Id>, ServerFnError> {
+    use super::schema::{backlogs, backlog_members};
+
+    backlogs::table
+        .inner_join(backlog_members::table)
+        .filter(backlogs::deleted_at.is_null())
+        .filter(backlog_members::user_id.eq(user.id))
+        .select(backlogs::id)
+        .load::<Id>(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Visible backlogs that aren't archived, whose tasks show up outside their own backlog.
+pub async fn active_backlog_ids(conn: &mut DbConnection, user: &User) -> Result<Vec<Id>, ServerFnError> {
+    use super::schema::backlogs;
+
+    let archived = backlogs::table
+        .filter(backlogs::archived_at.is_not_null())
+        .select(backlogs::id)
+        .load::<Id>(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let mut visible = visible_backlog_ids(conn, user).await?;
+    visible.retain(|backlog| !archived.contains(backlog));
+    Ok(visible)
+}
+
+/// Ids of the projects `user` can see: their own, and those of the backlogs they can see.
+pub async fn visible_project_ids(conn: &mut DbConnection, user: &User) -> Result<Vec<Id>, ServerFnError> {
+    use super::schema::{backlogs, projects};
+
+    let visible = visible_backlog_ids(conn, user).await?;
+    let linked = backlogs::table
+        .filter(backlogs::id.eq_any(visible))
+        .filter(backlogs::project_id.is_not_null())
+        .select(backlogs::project_id.assume_not_null());
+
+    projects::table
+        .filter(projects::owner_id.eq(user.id).or(projects::id.eq_any(linked)))
+        .select(projects::id)
+        .load::<Id>(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+pub async fn require_project_visible(conn: &mut DbConnection, project: Id, user: &User) -> Result<(), ServerFnError> {
+    if visible_project_ids(conn, user).await?.contains(&project) {
+        Ok(())
+    } else {
+        Err(ServerFnError::new("Permission denied: access to project required"))
+    }
+}
+
+/// Projects and their milestones are only changed by the project's owner.
+pub async fn require_project_owner(conn: &mut DbConnection, project: Id, user: &User) -> Result<(), ServerFnError> {
+    use super::schema::projects;
+
+    let owner: Option<Option<Id>> = projects::table
+        .find(project)
+        .select(projects::owner_id)
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    match owner {
+        Some(Some(owner)) if owner == user.id => Ok(()),
+        _ => Err(ServerFnError::new("Permission denied: only the project's owner can change it")),
+    }
+}
@@ -0,0 +1,256 @@
+//! Per-user display preferences: the first day of the week, the language of day and month
+//! names, how dates are written, whether week numbers are shown and the user's time zone.
+//!
+//! Timestamps are stored in UTC; the time zone decides which day is "today" for the user and
+//! how timestamps are shown to them.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use diesel::{AsExpression, FromSqlRow, backend::Backend};
+#[cfg(feature = "server")]
+use diesel::deserialize::FromSql;
+#[cfg(feature = "server")]
+use diesel::serialize::{ToSql, Output};
+#[cfg(feature = "server")]
+use diesel::sql_types::Text;
+#[cfg(feature = "server")]
+use diesel::sqlite::Sqlite;
+
+/// Languages day and month names are available in.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Dutch,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 5] = [Locale::English, Locale::German, Locale::French, Locale::Spanish, Locale::Dutch];
+
+    /// The name of the language in itself, for picking it from a list.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::German => "Deutsch",
+            Locale::French => "Français",
+            Locale::Spanish => "Español",
+            Locale::Dutch => "Nederlands",
+        }
+    }
+
+    /// Day names from Monday to Sunday.
+    fn weekdays(&self) -> [&'static str; 7] {
+        match self {
+            Locale::English => ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+            Locale::German => ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+            Locale::French => ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+            Locale::Spanish => ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+            Locale::Dutch => ["maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag", "zondag"],
+        }
+    }
+
+    /// Month names from January to December.
+    fn months(&self) -> [&'static str; 12] {
+        match self {
+            Locale::English => ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"],
+            Locale::German => ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember"],
+            Locale::French => ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+            Locale::Spanish => ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+            Locale::Dutch => ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober", "november", "december"],
+        }
+    }
+
+    pub fn weekday_name(&self, weekday: Weekday) -> &'static str {
+        self.weekdays()[weekday.num_days_from_monday() as usize]
+    }
+
+    /// The first three letters of the day name, as used in column headers.
+    pub fn weekday_short(&self, weekday: Weekday) -> String {
+        self.weekday_name(weekday).chars().take(3).collect()
+    }
+
+    /// The name of a month numbered from 1.
+    pub fn month_name(&self, month: u32) -> &'static str {
+        self.months()[(month.clamp(1, 12) - 1) as usize]
+    }
+
+    pub fn month_short(&self, month: u32) -> String {
+        self.month_name(month).chars().take(3).collect()
+    }
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.label() == s)
+            .ok_or_else(|| format!("Unknown locale \"{}\"", s))
+    }
+}
+
+/// How dates are written.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DateFormat {
+    /// 2026-10-19
+    #[default]
+    Iso,
+    /// 19.10.2026
+    DayMonthYear,
+    /// 10/19/2026
+    MonthDayYear,
+    /// 19 October 2026, in the chosen language.
+    Long,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 4] = [DateFormat::Iso, DateFormat::DayMonthYear, DateFormat::MonthDayYear, DateFormat::Long];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateFormat::Iso => "2026-10-19",
+            DateFormat::DayMonthYear => "19.10.2026",
+            DateFormat::MonthDayYear => "10/19/2026",
+            DateFormat::Long => "19 October 2026",
+        }
+    }
+}
+
+impl std::str::FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DateFormat::ALL
+            .into_iter()
+            .find(|format| format.label() == s)
+            .ok_or_else(|| format!("Unknown date format \"{}\"", s))
+    }
+}
+
+/// Days a week can start on.
+pub const WEEK_STARTS: [Weekday; 3] = [Weekday::Mon, Weekday::Sat, Weekday::Sun];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "server", derive(AsExpression, FromSqlRow))]
+#[cfg_attr(feature = "server", diesel(sql_type = Text))]
+pub struct Preferences {
+    pub week_start: Weekday,
+    pub locale: Locale,
+    pub date_format: DateFormat,
+    /// Show ISO week numbers next to weeks.
+    pub show_week_numbers: bool,
+    /// The user's time zone by its IANA name, e.g. `Europe/Amsterdam`. Its offset is looked up
+    /// for every moment, so daylight saving time is followed.
+    #[serde(default)]
+    pub time_zone: Tz,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            week_start: Weekday::Mon,
+            locale: Locale::default(),
+            date_format: DateFormat::default(),
+            show_week_numbers: true,
+            time_zone: Tz::UTC,
+        }
+    }
+}
+
+impl Preferences {
+    /// The day the week containing `day` starts on.
+    pub fn week_of(&self, day: NaiveDate) -> NaiveDate {
+        day.week(self.week_start).first_day()
+    }
+
+    /// The days of the week, starting with the configured first one.
+    pub fn weekdays(&self) -> impl Iterator<Item = Weekday> {
+        std::iter::successors(Some(self.week_start), |weekday| Some(weekday.succ())).take(7)
+    }
+
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        match self.date_format {
+            DateFormat::Iso => date.format("%Y-%m-%d").to_string(),
+            DateFormat::DayMonthYear => date.format("%d.%m.%Y").to_string(),
+            DateFormat::MonthDayYear => date.format("%m/%d/%Y").to_string(),
+            DateFormat::Long => format!("{} {} {}", date.day(), self.locale.month_name(date.month()), date.year()),
+        }
+    }
+
+    /// The date with its day name, as in day headings.
+    pub fn format_day(&self, date: NaiveDate) -> String {
+        format!("{} {}", self.locale.weekday_short(date.weekday()), self.format_date(date))
+    }
+
+    pub fn format_month(&self, date: NaiveDate) -> String {
+        format!("{} {}", self.locale.month_name(date.month()), date.year())
+    }
+
+    pub fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.time_zone)
+    }
+
+    /// The current date where the user is, which may differ from the date in UTC.
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// A stored UTC timestamp in the user's time zone.
+    pub fn to_local(&self, timestamp: NaiveDateTime) -> DateTime<Tz> {
+        self.time_zone.from_utc_datetime(&timestamp)
+    }
+}
+
+/// The current time as stored in timestamp columns, which always hold UTC.
+pub fn utc_now() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+#[cfg(feature = "server")]
+impl ToSql<Text, Sqlite> for Preferences {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> diesel::serialize::Result {
+        out.set_value(serde_json::to_string(self)?);
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "server")]
+impl FromSql<Text, Sqlite> for Preferences {
+    fn from_sql(mut bytes: <Sqlite as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let s = std::str::from_utf8(bytes.read_blob())?;
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_times_follow_daylight_saving_time() {
+        let preferences = Preferences { time_zone: Tz::Europe__Amsterdam, ..Default::default() };
+        let winter = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap().and_hms_opt(23, 30, 0).unwrap();
+        let summer = NaiveDate::from_ymd_opt(2025, 7, 15).unwrap().and_hms_opt(22, 30, 0).unwrap();
+
+        assert_eq!(preferences.to_local(winter).naive_local(), NaiveDate::from_ymd_opt(2025, 1, 16).unwrap().and_hms_opt(0, 30, 0).unwrap());
+        assert_eq!(preferences.to_local(summer).naive_local(), NaiveDate::from_ymd_opt(2025, 7, 16).unwrap().and_hms_opt(0, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn time_zones_are_stored_by_name() {
+        let preferences = Preferences { time_zone: Tz::America__New_York, ..Default::default() };
+        let json = serde_json::to_string(&preferences).unwrap();
+        assert!(json.contains("\"time_zone\":\"America/New_York\""));
+        assert_eq!(serde_json::from_str::<Preferences>(&json).unwrap(), preferences);
+
+        let without = r#"{"week_start":"Sun","locale":"German","date_format":"Iso","show_week_numbers":false}"#;
+        assert_eq!(serde_json::from_str::<Preferences>(without).unwrap().time_zone, Tz::UTC);
+        assert!(serde_json::from_str::<Preferences>(&json.replace("America/New_York", "Mars/Olympus")).is_err());
+    }
+}
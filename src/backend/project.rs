@@ -0,0 +1,27 @@
+use chrono::{Days, NaiveDate};
+use super::model::*;
+
+/// How far back a burndown goes at most.
+const BURNDOWN_DAYS: u64 = 90;
+
+/// Open tasks at the end of each day, from when the first task was created up to `today`. A
+/// completed task counts as closed from the day it was completed.
+pub fn burndown(tasks: &[Task], today: NaiveDate) -> Vec<BurndownPoint> {
+    let Some(first) = tasks.iter().map(|t| t.created_at.date()).min() else {
+        return vec![];
+    };
+    let start = first.max(today.checked_sub_days(Days::new(BURNDOWN_DAYS)).unwrap_or(first));
+
+    start
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| {
+            let remaining = tasks
+                .iter()
+                .filter(|t| t.created_at.date() <= day)
+                .filter(|t| !(t.completed && t.completed_at.is_some_and(|c| c.date() <= day)))
+                .count();
+            BurndownPoint { date: day, remaining: remaining as i32 }
+        })
+        .collect()
+}
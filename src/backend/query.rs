@@ -0,0 +1,353 @@
+//! A small query language for filtering tasks, e.g. `role:work is:open due<+7d !urgent text:"invoice"`.
+//!
+//! Terms are separated by whitespace and all have to match:
+//!
+//! - `is:open`, `is:done`, `is:important`, `is:urgent`, `is:mine`, `is:archived`; the flags can
+//!   also be written bare, as in `urgent`, and negated with a leading `!`, as in `!urgent`
+//! - `role:<name>` with the name of a role and `tag:<name>` with the name of a tag, both
+//!   case-insensitive
+//! - `due:<date>`, `due<<date>`, `due<=<date>`, `due><date>`, `due>=<date>` and `due:none`, where a
+//!   date is `today`, `tomorrow`, `yesterday`, `2026-11-01` or relative like `+7d`, `-1d`, `+2w`
+//! - `text:<words>` or any other bare word, matched against title and content
+//! - `sort:created|date|title|updated|priority` and `limit:<n>`
+//!
+//! Values containing spaces are quoted, as in `text:"call bank"`.
+
+use chrono::{Days, NaiveDate};
+use super::model::*;
+
+/// A query that could not be parsed, with the position of the character in the input where the
+/// problem is, counting from zero.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (at column {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// An error at a byte offset; `parse_query` turns it into a character position on the way out.
+fn error(position: usize, message: impl Into<String>) -> QueryError {
+    QueryError { position, message: message.into() }
+}
+
+/// Splits the input on whitespace outside of quotes, keeping each term's offset.
+fn terms(input: &str) -> Result<Vec<(usize, &str)>, QueryError> {
+    let mut terms = vec![];
+    let mut start = None;
+    let mut quote_start = None;
+
+    for (i, c) in input.char_indices() {
+        if c == '"' {
+            quote_start = if quote_start.is_some() { None } else { Some(i) };
+        }
+        if c.is_whitespace() && quote_start.is_none() {
+            if let Some(s) = start.take() {
+                terms.push((s, &input[s..i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+
+    if let Some(position) = quote_start {
+        return Err(error(position, "Unterminated quote"));
+    }
+    if let Some(s) = start {
+        terms.push((s, &input[s..]));
+    }
+    Ok(terms)
+}
+
+fn unquote(value: &str, position: usize) -> Result<&str, QueryError> {
+    match value.strip_prefix('"') {
+        Some(rest) => rest.strip_suffix('"').ok_or_else(|| error(position, "Unterminated quote")),
+        None => Ok(value),
+    }
+}
+
+/// Splits `due<=today` into key, operator and value, with the offset of the value in the term.
+fn split_term(term: &str) -> Option<(&str, &str, &str, usize)> {
+    let quote = term.find('"').unwrap_or(term.len());
+    let op_start = term[..quote].find([':', '<', '>', '='])?;
+    let op_len = if term[op_start + 1..].starts_with('=') { 2 } else { 1 };
+    let op = &term[op_start..op_start + op_len];
+    Some((&term[..op_start], op, &term[op_start + op_len..], op_start + op_len))
+}
+
+fn parse_date(value: &str, today: NaiveDate, position: usize) -> Result<NaiveDate, QueryError> {
+    let date = match value {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        "yesterday" => today.pred_opt(),
+        _ if value.starts_with(['+', '-']) => {
+            let rest = &value[1..];
+            let (amount, unit) = rest.split_at(rest.char_indices().last().map_or(0, |(i, _)| i));
+            let days = match (amount.parse::<u64>(), unit) {
+                (Ok(n), "d") => n,
+                (Ok(n), "w") => n * 7,
+                _ => return Err(error(position, format!("Expected a relative date like +7d or -2w, found \"{}\"", value))),
+            };
+            if value.starts_with('+') {
+                today.checked_add_days(Days::new(days))
+            } else {
+                today.checked_sub_days(Days::new(days))
+            }
+        },
+        _ => NaiveDate::parse_from_str(value, "%Y-%m-%d").ok(),
+    };
+    date.ok_or_else(|| error(position, format!("Expected a date like today, +7d or 2026-11-01, found \"{}\"", value)))
+}
+
+/// Applies a flag term such as `is:urgent` or `!urgent`.
+fn apply_flag(filter: &mut TaskFilter, flag: &str, negated: bool, position: usize) -> Result<(), QueryError> {
+    match flag {
+        "open" => filter.completed = Some(negated),
+        "done" | "completed" => filter.completed = Some(!negated),
+        "important" => filter.important = Some(!negated),
+        "urgent" => filter.urgent = Some(!negated),
+        "mine" if !negated => filter.assigned_to_me = true,
+        "mine" => return Err(error(position, "is:mine can't be negated")),
+        "archived" => filter.archived = !negated,
+        other => return Err(error(position, format!("Unknown flag \"{}\", expected open, done, important, urgent, mine or archived", other))),
+    }
+    Ok(())
+}
+
+fn is_flag(word: &str) -> bool {
+    matches!(word, "open" | "done" | "completed" | "important" | "urgent" | "mine" | "archived")
+}
+
+/// Parses a query into the filter `get_tasks` takes.
+///
+/// Relative dates are resolved against `today`, role and tag names against `roles` and `tags`.
+/// Tasks in any visible backlog and on any date match unless the query narrows them down.
+pub fn parse_query(input: &str, today: NaiveDate, roles: &[Role], tags: &[Tag]) -> Result<TaskFilter, QueryError> {
+    parse(input, today, roles, tags).map_err(|e| QueryError {
+        position: input.get(..e.position).map_or(e.position, |before| before.chars().count()),
+        ..e
+    })
+}
+
+fn parse(input: &str, today: NaiveDate, roles: &[Role], tags: &[Tag]) -> Result<TaskFilter, QueryError> {
+    let mut filter = TaskFilter {
+        scheduled: DateFilter::Any,
+        backlog: BacklogFilter::Any,
+        ..Default::default()
+    };
+    let mut text = vec![];
+    let (mut from, mut to) = (None, None);
+
+    for (start, term) in terms(input)? {
+        let (negated, term, start) = match term.strip_prefix('!') {
+            Some(rest) => (true, rest, start + 1),
+            None => (false, term, start),
+        };
+
+        let Some((key, op, value, value_offset)) = split_term(term) else {
+            let word = unquote(term, start)?;
+            if is_flag(word) {
+                apply_flag(&mut filter, word, negated, start)?;
+            } else if negated {
+                return Err(error(start - 1, "Only flags like !urgent can be negated"));
+            } else {
+                text.push(word.to_string());
+            }
+            continue;
+        };
+
+        let value_pos = start + value_offset;
+        let value = unquote(value, value_pos)?;
+        if negated && key != "is" {
+            return Err(error(start - 1, format!("{}: can't be negated", key)));
+        }
+        if op != ":" && key != "due" {
+            return Err(error(start + key.len(), format!("{} only supports \":\"", key)));
+        }
+        if value.is_empty() {
+            return Err(error(value_pos, format!("Missing value after {}{}", key, op)));
+        }
+
+        match key {
+            "is" => apply_flag(&mut filter, value, negated, value_pos)?,
+            "role" => {
+                let role = roles
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(value))
+                    .ok_or_else(|| error(value_pos, format!("Unknown role \"{}\"", value)))?;
+                filter.role_id = Some(role.id);
+            },
+            "tag" => {
+                let tag = tags
+                    .iter()
+                    .find(|t| t.is_named(value))
+                    .ok_or_else(|| error(value_pos, format!("Unknown tag \"{}\"", value)))?;
+                filter.tags.push(tag.id);
+            },
+            "text" => text.push(value.to_string()),
+            "due" if op == ":" && value == "none" => filter.scheduled = DateFilter::Unscheduled,
+            "due" => {
+                let date = parse_date(value, today, value_pos)?;
+                match op {
+                    ":" | "=" => (from, to) = (Some(date), Some(date)),
+                    "<" => to = date.pred_opt(),
+                    "<=" => to = Some(date),
+                    ">" => from = date.succ_opt(),
+                    ">=" => from = Some(date),
+                    _ => return Err(error(start + key.len(), format!("Unknown operator \"{}\"", op))),
+                }
+            },
+            "sort" => {
+                filter.sort = match value {
+                    "created" => TaskSort::Created,
+                    "date" | "due" => TaskSort::Scheduled,
+                    "title" => TaskSort::Title,
+                    "updated" => TaskSort::Updated,
+                    "priority" => TaskSort::Priority,
+                    other => return Err(error(value_pos, format!("Unknown sort order \"{}\"", other))),
+                };
+            },
+            "limit" => {
+                let limit = value.parse::<i64>().ok().filter(|n| *n > 0)
+                    .ok_or_else(|| error(value_pos, format!("Expected a positive number, found \"{}\"", value)))?;
+                filter.limit = Some(limit);
+            },
+            other => return Err(error(start, format!("Unknown field \"{}\"", other))),
+        }
+    }
+
+    if from.is_some() || to.is_some() {
+        if filter.scheduled == DateFilter::Unscheduled {
+            return Err(error(0, "due:none can't be combined with a due date"));
+        }
+        filter.scheduled = match (from, to) {
+            (Some(from), Some(to)) if from == to => DateFilter::On(from),
+            _ => DateFilter::Between(from, to),
+        };
+    }
+    if !text.is_empty() {
+        filter.text = Some(text.join(" "));
+    }
+
+    Ok(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::tests::date;
+
+    const ROLE: Id = Id(uuid::Uuid::from_u128(1));
+    const TAG: Id = Id(uuid::Uuid::from_u128(2));
+
+    /// Parses on 2025-03-10, with a "Work" role and a "Home" tag.
+    fn parse(input: &str) -> Result<TaskFilter, QueryError> {
+        let roles = [Role { id: ROLE, name: "Work".to_string() }];
+        let tags = [Tag { id: TAG, name: "Home".to_string(), color: TAG_COLORS[0].to_string() }];
+        parse_query(input, date(10), &roles, &tags)
+    }
+
+    fn scheduled(input: &str) -> DateFilter {
+        parse(input).expect(input).scheduled
+    }
+
+    fn error_at(input: &str) -> usize {
+        parse(input).expect_err(input).position
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        let filter = parse("  ").unwrap();
+        assert_eq!(filter, TaskFilter { scheduled: DateFilter::Any, backlog: BacklogFilter::Any, ..Default::default() });
+    }
+
+    #[test]
+    fn flags_and_negation() {
+        let filter = parse("is:open urgent !important is:mine").unwrap();
+        assert_eq!(filter.completed, Some(false));
+        assert_eq!(filter.urgent, Some(true));
+        assert_eq!(filter.important, Some(false));
+        assert!(filter.assigned_to_me);
+
+        assert_eq!(parse("!is:open").unwrap().completed, Some(true));
+        assert_eq!(parse("done").unwrap().completed, Some(true));
+        assert!(parse("is:archived").unwrap().archived);
+        assert!(parse("!mine").is_err());
+        assert!(parse("is:later").is_err());
+    }
+
+    #[test]
+    fn roles_tags_and_text() {
+        let filter = parse("role:work tag:HOME invoice text:\"call bank\" \"due soon\"").unwrap();
+        assert_eq!(filter.role_id, Some(ROLE));
+        assert_eq!(filter.tags, vec![TAG]);
+        assert_eq!(filter.text.as_deref(), Some("invoice call bank due soon"));
+
+        assert!(parse("role:play").is_err());
+        assert!(parse("tag:office").is_err());
+    }
+
+    #[test]
+    fn due_operators() {
+        assert_eq!(scheduled("due:today"), DateFilter::On(date(10)));
+        assert_eq!(scheduled("due=tomorrow"), DateFilter::On(date(11)));
+        assert_eq!(scheduled("due<today"), DateFilter::Between(None, Some(date(9))));
+        assert_eq!(scheduled("due<=today"), DateFilter::Between(None, Some(date(10))));
+        assert_eq!(scheduled("due>today"), DateFilter::Between(Some(date(11)), None));
+        assert_eq!(scheduled("due>=today"), DateFilter::Between(Some(date(10)), None));
+        assert_eq!(scheduled("due>=yesterday due<+1w"), DateFilter::Between(Some(date(9)), Some(date(16))));
+        assert_eq!(scheduled("due>=today due<=today"), DateFilter::On(date(10)));
+        assert_eq!(scheduled("due:none"), DateFilter::Unscheduled);
+        assert!(parse("due:none due<today").is_err());
+        assert!(parse("role<work").is_err());
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(scheduled("due:+7d"), DateFilter::On(date(17)));
+        assert_eq!(scheduled("due:-1d"), DateFilter::On(date(9)));
+        assert_eq!(scheduled("due:+2w"), DateFilter::On(date(24)));
+        assert_eq!(scheduled("due:-0d"), DateFilter::On(date(10)));
+        assert_eq!(scheduled("due:2025-04-01"), DateFilter::On(NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()));
+        for invalid in ["due:+7", "due:+d", "due:+7m", "due:soon", "due:2025-02-30", "due:+"] {
+            assert!(parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn sort_and_limit() {
+        let filter = parse("sort:priority limit:5").unwrap();
+        assert_eq!(filter.sort, TaskSort::Priority);
+        assert_eq!(filter.limit, Some(5));
+        assert_eq!(parse("sort:due").unwrap().sort, TaskSort::Scheduled);
+        assert!(parse("sort:size").is_err());
+        assert!(parse("limit:0").is_err());
+        assert!(parse("limit:many").is_err());
+    }
+
+    #[test]
+    fn error_positions() {
+        assert_eq!(error_at("is:open role:play"), 13);
+        assert_eq!(error_at("text:\"call bank"), 5);
+        assert_eq!(error_at("urgent !bank"), 7);
+        assert_eq!(error_at("!role:work"), 0);
+        assert_eq!(error_at("role>work"), 4);
+        assert_eq!(error_at("due:"), 4);
+        assert_eq!(error_at("owner:me"), 0);
+    }
+
+    #[test]
+    fn error_positions_count_characters() {
+        // "é" and "ü" take two bytes each
+        assert_eq!(error_at("café müsli limit:x"), 17);
+        assert_eq!(error_at("text:\"crème brûlée\" due:later"), 24);
+        let error = parse("über sort:size").unwrap_err();
+        assert_eq!(error.to_string(), "Unknown sort order \"size\" (at column 11)");
+    }
+}
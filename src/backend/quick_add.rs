@@ -0,0 +1,214 @@
+//! Inline tokens in the task input, e.g. `Pay rent next fri #home @admin ! ~15m`.
+//!
+//! - `today`, `tomorrow` and `next <weekday>` schedule the task
+//! - `every day` and `every <weekday>` make it recur, starting on the next matching day
+//! - `#name` puts it in a backlog and `@name` gives it a role
+//! - `!` marks it important, `!!` urgent and `!!!` both
+//! - `~30m`, `~2h` or `~1h30m` set the estimate
+//! - a leading `\` keeps a word in the title, as in `Read \#1 on \today`
+//!
+//! Everything else is the title.
+
+use chrono::{NaiveDate, Weekday};
+use super::model::*;
+use super::preferences::Preferences;
+
+/// Minutes in an estimate like `30m`, `2h` or `1h30m`.
+fn parse_estimate(value: &str) -> Option<i32> {
+    let (hours, rest) = match value.split_once('h') {
+        Some((hours, rest)) => (hours.parse::<i32>().ok()?, rest),
+        None => (0, value),
+    };
+    let minutes = match rest.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<i32>().ok()?,
+        None if rest.is_empty() && value.ends_with('h') => 0,
+        None => return None,
+    };
+    Some(hours * 60 + minutes).filter(|m| *m > 0)
+}
+
+pub fn parse_quick_add(input: &str, today: NaiveDate) -> QuickAdd {
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let mut parsed = QuickAdd::default();
+    let mut title = vec![];
+    let mut i = 0;
+
+    while i < words.len() {
+        let word = words[i];
+        let lower = word.to_lowercase();
+        let next_weekday_word = words.get(i + 1).and_then(|w| w.parse::<Weekday>().ok());
+
+        match (lower.as_str(), next_weekday_word) {
+            ("today", _) => parsed.scheduled_date = Some(today),
+            ("tomorrow", _) => parsed.scheduled_date = today.succ_opt(),
+            ("next", Some(weekday)) => {
+                parsed.scheduled_date = today.succ_opt().map(|day| next_weekday(day, weekday));
+                i += 1;
+            },
+            ("every", _) if words.get(i + 1).is_some_and(|w| w.eq_ignore_ascii_case("day")) => {
+                parsed.recurrence = Some(Recurrence::Daily);
+                parsed.scheduled_date.get_or_insert(today);
+                i += 1;
+            },
+            ("every", Some(weekday)) => {
+                parsed.recurrence = Some(Recurrence::Weekly(weekday));
+                parsed.scheduled_date.get_or_insert(next_weekday(today, weekday));
+                i += 1;
+            },
+            ("!", _) => parsed.important = true,
+            ("!!", _) => parsed.urgent = true,
+            ("!!!", _) => {
+                parsed.important = true;
+                parsed.urgent = true;
+            },
+            _ if word.len() > 1 && word.starts_with('\\') => title.push(&word[1..]),
+            _ if word.len() > 1 && word.starts_with('#') => parsed.backlog = Some(word[1..].to_string()),
+            _ if word.len() > 1 && word.starts_with('@') => parsed.role = Some(word[1..].to_string()),
+            _ if word.starts_with('~') && parse_estimate(&lower[1..]).is_some() => {
+                parsed.estimate_minutes = parse_estimate(&lower[1..]);
+            },
+            _ => title.push(word),
+        }
+        i += 1;
+    }
+
+    parsed.title = title.join(" ");
+    parsed
+}
+
+/// Short labels for everything the tokens set, shown while typing.
+pub fn quick_add_chips(parsed: &QuickAdd, preferences: &Preferences) -> Vec<String> {
+    let mut chips = vec![];
+    if let Some(date) = parsed.scheduled_date {
+        chips.push(preferences.format_day(date));
+    }
+    match parsed.recurrence {
+        Some(Recurrence::Daily) => chips.push("Every day".to_string()),
+        Some(Recurrence::Weekly(weekday)) => chips.push(format!("Every {}", weekday)),
+        None => {},
+    }
+    if let Some(backlog) = &parsed.backlog {
+        chips.push(format!("#{}", backlog));
+    }
+    if let Some(role) = &parsed.role {
+        chips.push(format!("@{}", role));
+    }
+    if parsed.important {
+        chips.push("Important".to_string());
+    }
+    if parsed.urgent {
+        chips.push("Urgent".to_string());
+    }
+    if let Some(minutes) = parsed.estimate_minutes {
+        chips.push(format!("~{}", format_minutes(minutes)));
+    }
+    chips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::tests::date;
+    use super::super::preferences::{DateFormat, Locale};
+
+    /// Parses on Monday 2025-03-10.
+    fn parse(input: &str) -> QuickAdd {
+        parse_quick_add(input, date(10))
+    }
+
+    #[test]
+    fn plain_title() {
+        let parsed = parse("  Pay   rent ");
+        assert_eq!(parsed, QuickAdd { title: "Pay rent".to_string(), ..Default::default() });
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse("Pay rent today").scheduled_date, Some(date(10)));
+        assert_eq!(parse("Pay rent Tomorrow").scheduled_date, Some(date(11)));
+        assert_eq!(parse("Pay rent next fri").scheduled_date, Some(date(14)));
+        assert_eq!(parse("Pay rent next monday").scheduled_date, Some(date(17)));
+        assert_eq!(parse("Pay rent next fri").title, "Pay rent");
+        assert_eq!(parse("next time").scheduled_date, None);
+        assert_eq!(parse("next time").title, "next time");
+    }
+
+    #[test]
+    fn recurrence() {
+        let daily = parse("Stretch every day");
+        assert_eq!(daily.recurrence, Some(Recurrence::Daily));
+        assert_eq!(daily.scheduled_date, Some(date(10)));
+        assert_eq!(daily.title, "Stretch");
+
+        let weekly = parse("Bins every thu");
+        assert_eq!(weekly.recurrence, Some(Recurrence::Weekly(Weekday::Thu)));
+        assert_eq!(weekly.scheduled_date, Some(date(13)));
+
+        let later = parse("Bins every thu next fri");
+        assert_eq!(later.scheduled_date, Some(date(14)));
+    }
+
+    #[test]
+    fn backlog_and_role() {
+        let parsed = parse("Fix tap #home @admin");
+        assert_eq!(parsed.backlog.as_deref(), Some("home"));
+        assert_eq!(parsed.role.as_deref(), Some("admin"));
+        assert_eq!(parsed.title, "Fix tap");
+
+        let bare = parse("Fix # and @");
+        assert_eq!(bare.backlog, None);
+        assert_eq!(bare.role, None);
+        assert_eq!(bare.title, "Fix # and @");
+    }
+
+    #[test]
+    fn flags() {
+        let important = parse("Call ! mum");
+        assert!(important.important && !important.urgent);
+        assert_eq!(important.title, "Call mum");
+
+        let urgent = parse("Call mum !!");
+        assert!(!urgent.important && urgent.urgent);
+
+        let both = parse("Call mum !!!");
+        assert!(both.important && both.urgent);
+
+        let word = parse("Call mum!");
+        assert!(!word.important);
+        assert_eq!(word.title, "Call mum!");
+    }
+
+    #[test]
+    fn estimates() {
+        assert_eq!(parse("Read ~30m").estimate_minutes, Some(30));
+        assert_eq!(parse("Read ~2h").estimate_minutes, Some(120));
+        assert_eq!(parse("Read ~1H30M").estimate_minutes, Some(90));
+        for invalid in ["Read ~", "Read ~0m", "Read ~h", "Read ~30", "Read ~1h30"] {
+            let parsed = parse(invalid);
+            assert_eq!(parsed.estimate_minutes, None, "{}", invalid);
+            assert_eq!(parsed.title, invalid);
+        }
+    }
+
+    #[test]
+    fn escaped_words_stay_in_the_title() {
+        let parsed = parse("Read \\#1 on \\today \\! \\~2h today");
+        assert_eq!(parsed.title, "Read #1 on today ! ~2h");
+        assert_eq!(parsed.backlog, None);
+        assert!(!parsed.important);
+        assert_eq!(parsed.estimate_minutes, None);
+        assert_eq!(parsed.scheduled_date, Some(date(10)));
+        assert_eq!(parse("back\\slash \\").title, "back\\slash \\");
+    }
+
+    #[test]
+    fn chips_follow_preferences() {
+        let parsed = parse("Pay rent tomorrow #home @admin !!! ~1h30m");
+        let chips = quick_add_chips(&parsed, &Preferences::default());
+        assert_eq!(chips[0], Preferences::default().format_day(date(11)));
+        assert_eq!(chips[1..], ["#home", "@admin", "Important", "Urgent", "~1h30"]);
+
+        let german = Preferences { locale: Locale::German, date_format: DateFormat::DayMonthYear, ..Default::default() };
+        assert_eq!(quick_add_chips(&parsed, &german)[0], "Die 11.03.2025");
+    }
+}
@@ -0,0 +1,196 @@
+use dioxus::prelude::*;
+use diesel::prelude::*;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use chrono::{Days, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+use uuid::Uuid;
+use super::model::*;
+use super::history::{diff_tasks, new_batch, record_change};
+use super::permissions::require_task_role;
+use super::server::{DbConnection, TransactionError, store_task};
+use super::preferences::utc_now;
+
+/// Policy from `ROLLOVER_POLICY`: `move-to-today` (default), `copy-to-today` or `move-to-inbox`.
+pub fn configured_policy() -> RolloverPolicy {
+    match env::var("ROLLOVER_POLICY") {
+        Ok(value) => value.parse().unwrap_or_else(|e| {
+            eprintln!("{}, using the default", e);
+            RolloverPolicy::default()
+        }),
+        Err(_) => RolloverPolicy::default(),
+    }
+}
+
+/// Moves or copies open tasks scheduled before `today` according to `policy`.
+///
+/// Only the tasks of `actor` that they may edit are touched and changes are attributed to them. All tasks are
+/// rolled over in one transaction, so a failure part way leaves none of them moved.
+pub async fn roll_over(conn: &mut DbConnection, policy: RolloverPolicy, today: NaiveDate, actor: &User) -> Result<RolloverReport, ServerFnError> {
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        Ok(roll_over_overdue(conn, policy, today, actor).await?)
+    }.scope_boxed())
+    .await
+    .map_err(ServerFnError::from)
+}
+
+async fn roll_over_overdue(conn: &mut DbConnection, policy: RolloverPolicy, today: NaiveDate, actor: &User) -> Result<RolloverReport, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+    use super::schema::backlog_members;
+
+    let owned_backlogs = backlog_members::table
+        .filter(backlog_members::user_id.eq(actor.id))
+        .filter(backlog_members::role.eq(MemberRole::Owner))
+        .select(backlog_members::backlog_id)
+        .load::<Id>(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    // Only the actor's tasks, so each user's tasks move at their own midnight: their inbox, the
+    // backlog tasks assigned to them, and the unassigned ones of backlogs they own
+    let overdue = tasks
+        .select(Task::as_select())
+        .filter(deleted_at.is_null().and(completed.eq(false)).and(rolled_over_at.is_null()))
+        .filter(scheduled_date.lt(today))
+        .filter(
+            backlog_id.is_null().and(owner_id.eq(actor.id))
+                .or(backlog_id.is_not_null().and(assignee_id.eq(actor.id)))
+                .or(assignee_id.is_null().and(backlog_id.eq_any(owned_backlogs)))
+        )
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let actor_id = Some(actor.id);
+    let batch = new_batch();
+    let mut report = RolloverReport { policy, rolled_over: 0 };
+
+    for task in overdue {
+        if require_task_role(conn, &task, actor, MemberRole::Editor).await.is_err() {
+            continue;
+        }
+
+        match policy {
+            RolloverPolicy::MoveToToday | RolloverPolicy::MoveToInbox => {
+                let moved = Task {
+                    scheduled_date: if policy == RolloverPolicy::MoveToToday { Some(today) } else { None },
+                    start_time: if policy == RolloverPolicy::MoveToToday { task.start_time } else { None },
+                    end_time: if policy == RolloverPolicy::MoveToToday { task.end_time } else { None },
+                    postponed_count: task.postponed_count + 1,
+                    ..task.clone()
+                };
+                let moved = store_task(conn, moved).await?;
+                record_change(conn, task.id, actor_id, ChangeKind::Updated, diff_tasks(Some(&task), &moved)?, batch).await?;
+            },
+            RolloverPolicy::CopyToToday => {
+                let now = utc_now();
+                let copy = Task {
+                    id: Id(Uuid::now_v7()),
+                    scheduled_date: Some(today),
+                    postponed_count: task.postponed_count + 1,
+                    created_at: now,
+                    updated_at: None,
+                    version: 0,
+                    ..task.clone()
+                };
+                diesel::insert_into(tasks)
+                    .values(&copy)
+                    .execute(conn)
+                    .await
+                    .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+                record_change(conn, copy.id, actor_id, ChangeKind::Created, diff_tasks(None, &copy)?, batch).await?;
+
+                let original = store_task(conn, Task { rolled_over_at: Some(now), ..task.clone() }).await?;
+                record_change(conn, task.id, actor_id, ChangeKind::Updated, diff_tasks(Some(&task), &original)?, batch).await?;
+            },
+        }
+        report.rolled_over += 1;
+    }
+
+    Ok(report)
+}
+
+/// Longest the scheduler sleeps, so time zones users switch to are picked up within the hour.
+const MAX_SLEEP: Duration = Duration::from_secs(60 * 60);
+
+/// Starts automatic rollover as configured in `ROLLOVER_SCHEDULE`, a comma separated list of
+/// `startup` and `midnight`. Without it rollover only runs when triggered from the UI.
+///
+/// Scheduled runs go by each user's time zone: at midnight where a user is, the tasks they can
+/// edit are rolled over to their new day.
+pub fn start_scheduler() {
+    dotenvy::dotenv().ok();
+    let schedule = env::var("ROLLOVER_SCHEDULE").unwrap_or_default();
+    let at_startup = schedule.split(',').any(|s| s.trim() == "startup");
+    let at_midnight = schedule.split(',').any(|s| s.trim() == "midnight");
+    if !at_startup && !at_midnight {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Failed to start rollover scheduler: {}", e);
+                return;
+            }
+        };
+
+        // The day it was in each time zone in use when that zone was last looked at
+        let mut days: HashMap<Tz, NaiveDate> = HashMap::new();
+        run_scheduled(&runtime, &mut days, at_startup);
+        while at_midnight {
+            std::thread::sleep(until_next_midnight(days.keys().copied()));
+            run_scheduled(&runtime, &mut days, false);
+        }
+    });
+}
+
+/// Rolls over for the users in every time zone where a new day started since the last run.
+/// Zones not seen before are only rolled over with `new_zones`, otherwise their first rollover
+/// waits for their next midnight.
+fn run_scheduled(runtime: &tokio::runtime::Runtime, days: &mut HashMap<Tz, NaiveDate>, new_zones: bool) {
+    let users = match runtime.block_on(super::server::users_with_preferences()) {
+        Ok(users) => users,
+        Err(e) => {
+            eprintln!("Scheduled rollover failed: {}", e);
+            return;
+        }
+    };
+
+    let mut by_zone: HashMap<Tz, Vec<User>> = HashMap::new();
+    for (user, preferences) in users {
+        by_zone.entry(preferences.time_zone).or_default().push(user);
+    }
+
+    for (zone, users) in by_zone {
+        let today = Utc::now().with_timezone(&zone).date_naive();
+        let due = match days.insert(zone, today) {
+            Some(day) => day != today,
+            None => new_zones,
+        };
+        if due {
+            if let Err(e) = runtime.block_on(super::server::scheduled_rollover(&users, today)) {
+                eprintln!("Scheduled rollover in {} failed: {}", zone, e);
+            }
+        }
+    }
+}
+
+/// Time until the next midnight in any of `zones`, at most `MAX_SLEEP`.
+fn until_next_midnight(zones: impl Iterator<Item = Tz>) -> Duration {
+    let now = Utc::now();
+    zones
+        .filter_map(|zone| {
+            let tomorrow = now.with_timezone(&zone).date_naive().checked_add_days(Days::new(1))?;
+            // Where the clocks skip midnight, the day starts at the first moment after the gap
+            let midnight = zone.from_local_datetime(&tomorrow.and_hms_opt(0, 0, 0)?).earliest()
+                .or_else(|| zone.from_local_datetime(&tomorrow.and_hms_opt(1, 0, 0)?).earliest())?;
+            (midnight.with_timezone(&Utc) - now).to_std().ok()
+        })
+        .min()
+        .map_or(MAX_SLEEP, |until| until.min(MAX_SLEEP) + Duration::from_secs(1))
+}
@@ -1,9 +1,63 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    backlog_members (backlog_id, user_id) {
+        backlog_id -> Text,
+        user_id -> Text,
+        role -> Text,
+    }
+}
+
+diesel::table! {
+    backlog_columns (id) {
+        id -> Text,
+        backlog_id -> Text,
+        name -> Text,
+        position -> Integer,
+        wip_limit -> Nullable<Integer>,
+    }
+}
+
 diesel::table! {
     backlogs (id) {
         id -> Text,
         name -> Text,
+        project_id -> Nullable<Text>,
+        position -> Integer,
+        archived_at -> Nullable<Timestamp>,
+        deleted_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    capacities (id) {
+        id -> Text,
+        user_id -> Text,
+        weekday -> Nullable<Integer>,
+        date -> Nullable<Date>,
+        minutes -> Integer,
+    }
+}
+
+diesel::table! {
+    milestones (id) {
+        id -> Text,
+        project_id -> Text,
+        name -> Text,
+        due_date -> Nullable<Date>,
+        position -> Integer,
+        completed -> Bool,
+    }
+}
+
+diesel::table! {
+    projects (id) {
+        id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        status -> Text,
+        target_date -> Nullable<Date>,
+        owner_id -> Nullable<Text>,
     }
 }
 
@@ -14,6 +68,61 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    saved_views (id) {
+        id -> Text,
+        owner_id -> Text,
+        name -> Text,
+        filter -> Text,
+        upcoming_days -> Nullable<Integer>,
+        query -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    sessions (token) {
+        token -> Text,
+        user_id -> Text,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Text,
+        name -> Text,
+        color -> Text,
+    }
+}
+
+diesel::table! {
+    task_changes (id) {
+        id -> Text,
+        task_id -> Text,
+        actor_id -> Nullable<Text>,
+        kind -> Text,
+        changes -> Text,
+        changed_at -> Timestamp,
+        undone_at -> Nullable<Timestamp>,
+        batch_id -> Text,
+    }
+}
+
+diesel::table! {
+    task_dependencies (blocker_id, blocked_id) {
+        blocker_id -> Text,
+        blocked_id -> Text,
+    }
+}
+
+diesel::table! {
+    task_tags (task_id, tag_id) {
+        task_id -> Text,
+        tag_id -> Text,
+    }
+}
+
 diesel::table! {
     tasks (id) {
         id -> Text,
@@ -28,14 +137,71 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Nullable<Timestamp>,
         deleted_at -> Nullable<Timestamp>,
+        assignee_id -> Nullable<Text>,
+        estimate_minutes -> Nullable<Integer>,
+        start_time -> Nullable<Time>,
+        end_time -> Nullable<Time>,
+        postponed_count -> Integer,
+        rolled_over_at -> Nullable<Timestamp>,
+        recurrence -> Nullable<Text>,
+        project_id -> Nullable<Text>,
+        status_id -> Nullable<Text>,
+        version -> Integer,
+        completed_at -> Nullable<Timestamp>,
+        archived_at -> Nullable<Timestamp>,
+        owner_id -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    user_preferences (user_id) {
+        user_id -> Text,
+        preferences -> Text,
+    }
+}
+
+diesel::table! {
+    users (id) {
+        id -> Text,
+        name -> Text,
     }
 }
 
+diesel::joinable!(backlog_columns -> backlogs (backlog_id));
+diesel::joinable!(backlog_members -> backlogs (backlog_id));
+diesel::joinable!(backlog_members -> users (user_id));
+diesel::joinable!(backlogs -> projects (project_id));
+diesel::joinable!(capacities -> users (user_id));
+diesel::joinable!(milestones -> projects (project_id));
+diesel::joinable!(projects -> users (owner_id));
+diesel::joinable!(saved_views -> users (owner_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(task_changes -> tasks (task_id));
+diesel::joinable!(task_changes -> users (actor_id));
+diesel::joinable!(task_tags -> tags (tag_id));
+diesel::joinable!(task_tags -> tasks (task_id));
+diesel::joinable!(tasks -> backlog_columns (status_id));
 diesel::joinable!(tasks -> backlogs (backlog_id));
+diesel::joinable!(tasks -> projects (project_id));
 diesel::joinable!(tasks -> roles (role_id));
+diesel::joinable!(tasks -> users (assignee_id));
+diesel::joinable!(user_preferences -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    backlog_columns,
+    backlog_members,
     backlogs,
+    capacities,
+    milestones,
+    projects,
     roles,
+    saved_views,
+    sessions,
+    tags,
+    task_changes,
+    task_dependencies,
+    task_tags,
     tasks,
+    user_preferences,
+    users,
 );
@@ -6,10 +6,14 @@ use diesel_async::{RunQueryDsl, AsyncConnection};
 #[cfg(feature = "server")]
 use diesel_async::sync_connection_wrapper::SyncConnectionWrapper;
 use super::model::*;
+use super::preferences::Preferences;
 #[cfg(feature = "server")]
-use chrono::Utc;
+use super::preferences::utc_now;
 use chrono::NaiveDate;
+#[cfg(feature = "server")]
+use chrono::Days;
 use std::env;
+use std::collections::BTreeMap;
 #[cfg(feature = "server")]
 use dotenvy::dotenv;
 #[cfg(feature = "server")]
@@ -18,192 +22,2221 @@ use uuid::Uuid;
 use std::sync::LazyLock;
 #[cfg(feature = "server")]
 use tokio::sync::Mutex;
+#[cfg(feature = "server")]
+use diesel_async::scoped_futures::ScopedFutureExt;
+#[cfg(feature = "server")]
+use super::permissions::*;
+#[cfg(feature = "server")]
+use super::history::*;
+#[cfg(feature = "server")]
+use super::capacity::rebalance;
+#[cfg(feature = "server")]
+use super::ics::to_ics;
+#[cfg(feature = "server")]
+use super::rollover::{configured_policy, roll_over};
+#[cfg(feature = "server")]
+use super::query::parse_query;
+#[cfg(feature = "server")]
+use super::dependencies::creates_cycle;
+#[cfg(feature = "server")]
+use super::project::burndown;
+#[cfg(feature = "server")]
+use super::integrity;
+use super::validation::*;
+
+#[cfg(feature = "server")]
+static DB_MUTEX: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+#[cfg(feature = "server")]
+pub(super) type DbConnection = SyncConnectionWrapper<SqliteConnection>;
+
+#[cfg(feature = "server")]
+async fn get_db_connection() -> Result<DbConnection, ConnectionError> {
+    dotenv().ok();
+    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let mut conn = SyncConnectionWrapper::<SqliteConnection>::establish(&database_url).await?;
+    // SQLite only enforces foreign keys, and their ON DELETE actions, when asked to per connection
+    diesel::sql_query("PRAGMA foreign_keys = ON")
+        .execute(&mut conn)
+        .await
+        .map_err(ConnectionError::CouldntSetupConfiguration)?;
+    Ok(conn)
+}
+
+#[cfg(feature = "server")]
+async fn load_task(conn: &mut DbConnection, task_id: Id) -> Result<Task, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+
+    tasks
+        .find(task_id)
+        .select(Task::as_select())
+        .first(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Writes every mutable column of a task, including its deletion state.
+#[cfg(feature = "server")]
+pub(super) async fn store_task(conn: &mut DbConnection, task: Task) -> Result<Task, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+
+    diesel::update(tasks.find(task.id))
+        .set((
+            title.eq(task.title), 
+            important.eq(task.important),
+            urgent.eq(task.urgent),
+            content.eq(task.content),
+            completed.eq(task.completed),
+            role_id.eq(task.role_id),
+            backlog_id.eq(task.backlog_id),
+            scheduled_date.eq(task.scheduled_date),
+            assignee_id.eq(task.assignee_id),
+            estimate_minutes.eq(task.estimate_minutes),
+            start_time.eq(task.start_time),
+            end_time.eq(task.end_time),
+            postponed_count.eq(task.postponed_count),
+            rolled_over_at.eq(task.rolled_over_at),
+            recurrence.eq(task.recurrence),
+            project_id.eq(task.project_id),
+            status_id.eq(task.status_id),
+            deleted_at.eq(task.deleted_at),
+            completed_at.eq(task.completed_at),
+            archived_at.eq(task.archived_at),
+            owner_id.eq(task.owner_id),
+            updated_at.eq(utc_now()),
+            version.eq(version + 1)))
+        .returning(Task::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Creates a task from what was typed into a list. Dates, backlogs and flags given as inline
+/// tokens take precedence over the `date` and `backlog_id` of the list it was typed into.
+#[server]
+pub async fn create_task(draft: QuickAdd, date: Option<NaiveDate>, backlog_id: Option<Id>) -> Result<Task, ServerFnError> {
+    use super::schema::tasks;
+
+    let mut new_task = Task {
+        id: Id(Uuid::now_v7()),
+        title: draft.title,
+        important: draft.important,
+        urgent: draft.urgent,
+        content: None,
+        completed: false,
+        role_id: None,
+        backlog_id,
+        scheduled_date: draft.scheduled_date.or(date),
+        created_at: utc_now(),
+        updated_at: None,
+        deleted_at: None,
+        version: 0,
+        completed_at: None,
+        archived_at: None,
+        owner_id: None,
+        assignee_id: None,
+        estimate_minutes: draft.estimate_minutes,
+        start_time: None,
+        end_time: None,
+        postponed_count: 0,
+        rolled_over_at: None,
+        recurrence: draft.recurrence,
+        project_id: None,
+        status_id: None,
+    };
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    new_task.owner_id = Some(user.id);
+
+    if let Some(backlog_name) = draft.backlog {
+        new_task.backlog_id = Some(find_backlog_by_name(&mut conn, &user, &backlog_name).await?);
+    }
+    if let Some(role_name) = draft.role {
+        new_task.role_id = Some(find_role_by_name(&mut conn, &role_name).await?);
+    }
+
+    let mut validator = Validator::new();
+    let new_task = validator.task(new_task);
+    check_task_references(&mut conn, &mut validator, &new_task).await?;
+    validator.finish()?;
+    require_task_role(&mut conn, &new_task, &user, MemberRole::Editor).await?;
+
+    let new_task = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        diesel::insert_into(tasks::table)
+            .values(&new_task)
+            .execute(conn)
+            .await?;
+
+        let changes = diff_tasks(None, &new_task)?;
+        record_change(conn, new_task.id, Some(user.id), ChangeKind::Created, changes, new_batch()).await?;
+        Ok(new_task)
+    }.scope_boxed())
+    .await?;
+
+    Ok(new_task)
+}
+
+#[server]
+pub async fn get_tasks(filter: TaskFilter) -> Result<Vec<Task>, ServerFnError> {
+    let mut validator = Validator::new();
+    validator.filter(&filter);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    query_tasks(&mut conn, filter, &user).await
+}
+
+/// Tasks scheduled from `start` to `end` grouped by day, with an entry for every day of the
+/// range. Views over several days make this one call instead of one `get_tasks` per day.
+#[server]
+pub async fn get_tasks_in_range(start: NaiveDate, end: NaiveDate) -> Result<BTreeMap<NaiveDate, Vec<Task>>, ServerFnError> {
+    let mut validator = Validator::new();
+    validator.date_range("end", start, end);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let filter = TaskFilter {
+        scheduled: DateFilter::Between(Some(start), Some(end)),
+        backlog: BacklogFilter::Any,
+        ..Default::default()
+    };
+    let taskvec = query_tasks(&mut conn, filter, &user).await?;
+
+    let mut days: BTreeMap<NaiveDate, Vec<Task>> = start.iter_days().take_while(|day| *day <= end).map(|day| (day, vec![])).collect();
+    for task in taskvec {
+        if let Some(day) = task.scheduled_date {
+            days.entry(day).or_default().push(task);
+        }
+    }
+    Ok(days)
+}
+
+/// A backlog visible to `user` with the given name, ignoring case.
+#[cfg(feature = "server")]
+async fn find_backlog_by_name(conn: &mut DbConnection, user: &User, backlog_name: &str) -> Result<Id, ServerFnError> {
+    use super::schema::backlogs::dsl::*;
+
+    let visible = active_backlog_ids(conn, user).await?;
+    let candidates = backlogs
+        .filter(id.eq_any(visible))
+        .select(Backlog::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    candidates
+        .into_iter()
+        .find(|backlog| backlog.name.eq_ignore_ascii_case(backlog_name))
+        .map(|backlog| backlog.id)
+        .ok_or_else(|| ServerFnError::new(format!("No backlog named \"{}\"", backlog_name)))
+}
+
+#[cfg(feature = "server")]
+async fn find_role_by_name(conn: &mut DbConnection, role_name: &str) -> Result<Id, ServerFnError> {
+    use super::schema::roles::dsl::*;
+
+    let candidates = roles
+        .select(Role::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    candidates
+        .into_iter()
+        .find(|role| role.name.eq_ignore_ascii_case(role_name))
+        .map(|role| role.id)
+        .ok_or_else(|| ServerFnError::new(format!("No role named \"{}\"", role_name)))
+}
+
+/// Tasks matching `filter` that `user` can see.
+#[cfg(feature = "server")]
+pub(super) async fn query_tasks(conn: &mut DbConnection, filter: TaskFilter, user: &User) -> Result<Vec<Task>, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+    use super::schema::task_tags;
+
+    let mut query = tasks
+        .select(Task::as_select())
+        .filter(deleted_at.is_null())
+        .into_boxed();
+
+    query = if filter.archived {
+        query.filter(archived_at.is_not_null())
+    } else {
+        query.filter(archived_at.is_null())
+    };
+
+    query = match filter.scheduled {
+        DateFilter::Unscheduled => query.filter(scheduled_date.is_null()),
+        DateFilter::On(date) => query.filter(scheduled_date.eq(date)),
+        DateFilter::Between(from, to) => {
+            query = query.filter(scheduled_date.is_not_null());
+            if let Some(from) = from {
+                query = query.filter(scheduled_date.ge(from));
+            }
+            if let Some(to) = to {
+                query = query.filter(scheduled_date.le(to));
+            }
+            query
+        },
+        DateFilter::Any => query,
+    };
+
+    query = match filter.backlog {
+        BacklogFilter::None => query.filter(backlog_id.is_null().and(owner_id.eq(user.id))),
+        BacklogFilter::In(backlog) => {
+            require_backlog_role(conn, backlog, user, MemberRole::Viewer).await?;
+            query.filter(backlog_id.eq(backlog))
+        },
+        BacklogFilter::Any => {
+            let visible = active_backlog_ids(conn, user).await?;
+            query.filter(backlog_id.is_null().and(owner_id.eq(user.id)).or(backlog_id.eq_any(visible)))
+        },
+    };
+
+    if filter.assigned_to_me {
+        query = query.filter(assignee_id.eq(user.id));
+    }
+    if let Some(is_completed) = filter.completed {
+        query = query.filter(completed.eq(is_completed));
+    }
+    if let Some(is_important) = filter.important {
+        query = query.filter(important.eq(is_important));
+    }
+    if let Some(is_urgent) = filter.urgent {
+        query = query.filter(urgent.eq(is_urgent));
+    }
+    if let Some(since) = filter.updated_since.and_then(|d| d.and_hms_opt(0, 0, 0)) {
+        query = query.filter(updated_at.ge(since));
+    }
+    if let Some(since) = filter.completed_since.and_then(|d| d.and_hms_opt(0, 0, 0)) {
+        query = query.filter(completed_at.ge(since));
+    }
+    if let Some(role) = filter.role_id {
+        query = query.filter(role_id.eq(role));
+    }
+    if let Some(text) = filter.text.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        // SQLite's LIKE is case-insensitive, wildcards typed by the user are matched literally
+        let pattern = format!("%{}%", text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        query = query.filter(
+            title.like(pattern.clone()).escape('\\')
+                .or(content.like(pattern).escape('\\'))
+        );
+    }
+
+    // Ids are time-ordered, so they double as creation order and a stable tie-breaker
+    query = match filter.sort {
+        TaskSort::Created => query.order(id.asc()),
+        TaskSort::Scheduled => query.order((scheduled_date.asc(), id.asc())),
+        TaskSort::Title => query.order((title.asc(), id.asc())),
+        TaskSort::Updated => query.order((updated_at.desc(), id.desc())),
+        TaskSort::Priority => query.order((important.desc(), urgent.desc(), id.asc())),
+    };
+    for tag in filter.tags {
+        let tagged = task_tags::table
+            .filter(task_tags::tag_id.eq(tag))
+            .select(task_tags::task_id);
+        query = query.filter(id.eq_any(tagged));
+    }
+
+    if let Some(limit) = filter.limit {
+        query = query.limit(limit);
+    }
+    if let Some(offset) = filter.offset {
+        query = query.offset(offset);
+    }
+
+    let taskvec = query
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(taskvec)
+}
+
+/// Runs a query from the command line on behalf of the configured user, returning the tasks
+/// along with the user's preferences for printing them.
+#[cfg(feature = "server")]
+pub(super) async fn command_line_query(input: &str) -> Result<(Vec<Task>, Preferences), ServerFnError> {
+    use super::schema::{roles, tags};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = find_or_create_user(&mut conn, &configured_user_name()).await?;
+
+    let rolesvec = roles::table
+        .select(Role::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    let tagvec = tags::table
+        .select(Tag::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    let preferences = preferences_of(&mut conn, &user).await?;
+    let filter = parse_query(input, preferences.today(), &rolesvec, &tagvec)
+        .map_err(|e| ServerFnError::new(e.to_string()))?;
+
+    let taskvec = query_tasks(&mut conn, filter, &user).await?;
+    Ok((taskvec, preferences))
+}
+
+/// Replaces a task with the client's copy. Prefer `patch_task`, which leaves fields the client
+/// didn't touch alone. A copy older than the stored task is refused with a conflict error.
+#[server]
+pub async fn update_task(task: Task) -> Result<Task, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let existing = load_task(conn, task.id).await?;
+        if task.version != existing.version {
+            return Err(conflict_error(&format!("\"{}\" was changed elsewhere, reload to see the changes", existing.title)).into());
+        }
+        Ok(write_task_update(conn, &user, existing, task, new_batch()).await?)
+    }.scope_boxed())
+    .await?;
+
+    Ok(task)
+}
+
+/// Changes the fields set in `patch`. With `if_match` set, a task that was written since the
+/// client read it is left alone and a conflict error is returned.
+#[server]
+pub async fn patch_task(patch: TaskPatch) -> Result<Task, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let existing = load_task(conn, patch.id).await?;
+        if patch.if_match.is_some_and(|expected| expected != existing.version) {
+            return Err(conflict_error(&format!("\"{}\" was changed elsewhere, reload to see the changes", existing.title)).into());
+        }
+        let task = patch.apply(&existing);
+        Ok(write_task_update(conn, &user, existing, task, new_batch()).await?)
+    }.scope_boxed())
+    .await?;
+
+    Ok(task)
+}
+
+/// Checks and stores the new state of a task, and records what changed in `batch`.
+#[cfg(feature = "server")]
+async fn write_task_update(conn: &mut DbConnection, user: &User, existing: Task, task: Task, batch: Id) -> Result<Task, ServerFnError> {
+    require_task_role(conn, &existing, user, MemberRole::Editor).await?;
+
+    // Clients can't hand a task to someone else. Taking one out of a backlog puts it in the
+    // inbox of whoever moved it.
+    let owner_id = match (existing.backlog_id, task.backlog_id) {
+        (Some(_), None) => Some(user.id),
+        _ => existing.owner_id,
+    };
+    let task = Task { owner_id, ..task };
+    require_task_role(conn, &task, user, MemberRole::Editor).await?;
+    if let Some(project) = task.project_id.filter(|p| Some(*p) != existing.project_id) {
+        require_project_visible(conn, project, user).await?;
+    }
+
+    let mut validator = Validator::new();
+    let task = validator.task(task);
+    check_task_references(conn, &mut validator, &task).await?;
+    validator.finish()?;
+
+    // Completing a recurring task hands its recurrence on to the next occurrence, so ticking it
+    // off again later doesn't schedule another one
+    let next_recurrence = task.recurrence.filter(|_| task.completed && !existing.completed);
+    let recurrence = if next_recurrence.is_some() { None } else { task.recurrence };
+
+    // Columns belong to a backlog; a task moved elsewhere starts over in the first column
+    let status_id = match task.status_id {
+        Some(status) if column_in_backlog(conn, status, task.backlog_id).await? => Some(status),
+        _ => None,
+    };
+
+    // Ticking a task off stamps it; reopening one also takes it out of the archive
+    let (completed_at, archived_at) = match (task.completed, existing.completed) {
+        (true, false) => (Some(utc_now()), None),
+        (true, true) => (existing.completed_at, existing.archived_at),
+        (false, _) => (None, None),
+    };
+
+    // Deletion goes through delete_task, clients can't undelete by sending a stale copy. Creation
+    // and rollover bookkeeping are the server's too.
+    let task = store_task(conn, Task {
+        created_at: existing.created_at,
+        deleted_at: existing.deleted_at,
+        postponed_count: existing.postponed_count,
+        rolled_over_at: existing.rolled_over_at,
+        completed_at,
+        archived_at,
+        recurrence,
+        status_id,
+        ..task
+    }).await?;
+
+    let changes = diff_tasks(Some(&existing), &task)?;
+    record_change(conn, task.id, Some(user.id), ChangeKind::Updated, changes, batch).await?;
+
+    if let Some(next_recurrence) = next_recurrence {
+        create_next_occurrence(conn, &task, next_recurrence, user, batch).await?;
+    }
+
+    Ok(task)
+}
+
+#[cfg(feature = "server")]
+async fn column_in_backlog(conn: &mut DbConnection, column: Id, backlog: Option<Id>) -> Result<bool, ServerFnError> {
+    use super::schema::backlog_columns::dsl::*;
+
+    let Some(backlog) = backlog else {
+        return Ok(false);
+    };
+    backlog_columns
+        .find(column)
+        .filter(backlog_id.eq(backlog))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map(|count| count > 0)
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Schedules a fresh copy of a completed recurring task on its next day. It is recorded in the
+/// batch of the completion, so undoing that removes the copy again.
+#[cfg(feature = "server")]
+async fn create_next_occurrence(conn: &mut DbConnection, task: &Task, recurrence: Recurrence, user: &User, batch: Id) -> Result<Task, ServerFnError> {
+    use super::schema::tasks;
+
+    let after = match task.scheduled_date {
+        Some(day) => day,
+        None => preferences_of(conn, user).await?.today(),
+    };
+    let next = Task {
+        id: Id(Uuid::now_v7()),
+        completed: false,
+        scheduled_date: Some(recurrence.next_after(after)),
+        created_at: utc_now(),
+        updated_at: None,
+        postponed_count: 0,
+        rolled_over_at: None,
+        recurrence: Some(recurrence),
+        version: 0,
+        completed_at: None,
+        archived_at: None,
+        ..task.clone()
+    };
+
+    diesel::insert_into(tasks::table)
+        .values(&next)
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+    record_change(conn, next.id, Some(user.id), ChangeKind::Created, diff_tasks(None, &next)?, batch).await?;
+
+    Ok(next)
+}
+
+#[server]
+pub async fn delete_task(task_id: Id) -> Result<(), ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let existing = load_task(conn, task_id).await?;
+        require_task_role(conn, &existing, &user, MemberRole::Editor).await?;
+
+        let deleted = store_task(conn, Task { deleted_at: Some(utc_now()), ..existing.clone() }).await?;
+
+        let changes = diff_tasks(Some(&existing), &deleted)?;
+        record_change(conn, task_id, Some(user.id), ChangeKind::Deleted, changes, new_batch()).await?;
+        Ok(())
+    }.scope_boxed())
+    .await?;
+
+    Ok(())
+}
+
+/// Lets the server errors of the helpers used inside a transaction roll it back.
+#[cfg(feature = "server")]
+pub(super) enum TransactionError {
+    Database(diesel::result::Error),
+    Server(ServerFnError),
+}
+
+#[cfg(feature = "server")]
+impl From<diesel::result::Error> for TransactionError {
+    fn from(e: diesel::result::Error) -> Self {
+        TransactionError::Database(e)
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<ServerFnError> for TransactionError {
+    fn from(e: ServerFnError) -> Self {
+        TransactionError::Server(e)
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<TransactionError> for ServerFnError {
+    fn from(e: TransactionError) -> Self {
+        match e {
+            TransactionError::Database(e) => ServerFnError::new(format!("Database transaction error: {}", e)),
+            TransactionError::Server(e) => e,
+        }
+    }
+}
+
+/// Applies `patch` to each of the tasks in one transaction, so either all of them change or, if
+/// one can't be changed, none do. Each task gets its own history entry, all in one batch.
+#[cfg(feature = "server")]
+async fn patch_tasks(task_ids: Vec<Id>, patch: impl Fn(&Task) -> TaskPatch + Send + Sync) -> Result<Vec<Task>, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let batch = new_batch();
+    let patched = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let mut patched = vec![];
+        for task_id in task_ids {
+            let existing = load_task(conn, task_id).await?;
+            let task = patch(&existing).apply(&existing);
+            patched.push(write_task_update(conn, &user, existing, task, batch).await?);
+        }
+        Ok(patched)
+    }.scope_boxed())
+    .await?;
+
+    Ok(patched)
+}
+
+/// Ticks off or reopens all the given tasks.
+#[server]
+pub async fn complete_tasks(task_ids: Vec<Id>, completed: bool) -> Result<Vec<Task>, ServerFnError> {
+    patch_tasks(task_ids, |task| TaskPatch { completed: Some(completed), ..TaskPatch::new(task) }).await
+}
+
+/// Schedules all the given tasks on `date`, or unschedules them. Their time blocks are kept.
+#[server]
+pub async fn schedule_tasks(task_ids: Vec<Id>, date: Option<NaiveDate>) -> Result<Vec<Task>, ServerFnError> {
+    patch_tasks(task_ids, |task| TaskPatch { scheduled_date: Some(date), ..TaskPatch::new(task) }).await
+}
+
+/// Moves all the given tasks into a backlog, or into the inbox when `backlog` is `None`.
+#[server]
+pub async fn move_tasks_to_backlog(task_ids: Vec<Id>, backlog: Option<Id>) -> Result<Vec<Task>, ServerFnError> {
+    patch_tasks(task_ids, |task| TaskPatch { backlog_id: Some(backlog), ..TaskPatch::new(task) }).await
+}
+
+#[server]
+pub async fn set_tasks_role(task_ids: Vec<Id>, role: Option<Id>) -> Result<Vec<Task>, ServerFnError> {
+    patch_tasks(task_ids, |task| TaskPatch { role_id: Some(role), ..TaskPatch::new(task) }).await
+}
+
+/// Sets the important and urgent flags of all the given tasks; a `None` flag is left as it is.
+#[server]
+pub async fn set_tasks_flags(task_ids: Vec<Id>, important: Option<bool>, urgent: Option<bool>) -> Result<Vec<Task>, ServerFnError> {
+    patch_tasks(task_ids, |task| TaskPatch { important, urgent, ..TaskPatch::new(task) }).await
+}
+
+/// Deletes all the given tasks in one transaction.
+#[server]
+pub async fn delete_tasks(task_ids: Vec<Id>) -> Result<(), ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let now = utc_now();
+    let batch = new_batch();
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        for task_id in task_ids {
+            let existing = load_task(conn, task_id).await?;
+            require_task_role(conn, &existing, &user, MemberRole::Editor).await?;
+            let deleted = store_task(conn, Task { deleted_at: Some(now), ..existing.clone() }).await?;
+            record_change(conn, task_id, Some(user.id), ChangeKind::Deleted, diff_tasks(Some(&existing), &deleted)?, batch).await?;
+        }
+        Ok(())
+    }.scope_boxed())
+    .await?;
+
+    Ok(())
+}
+
+/// Archives the tasks the user can edit that were completed more than `days` days ago, so they
+/// stop showing up in lists. Returns how many were archived.
+#[server]
+pub async fn archive_completed(days: i32) -> Result<usize, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+
+    let mut validator = Validator::new();
+    validator.number("days", Some(days), 0..=RANGE_DAYS_MAX as i32);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let now = utc_now();
+    let cutoff = now - chrono::Duration::days(days as i64);
+    let done = tasks
+        .filter(deleted_at.is_null())
+        .filter(archived_at.is_null())
+        .filter(completed.eq(true))
+        .filter(completed_at.le(cutoff))
+        .select(Task::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let batch = new_batch();
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let mut archived = 0;
+        for existing in done {
+            if require_task_role(conn, &existing, &user, MemberRole::Editor).await.is_err() {
+                continue;
+            }
+            let task = store_task(conn, Task { archived_at: Some(now), ..existing.clone() }).await?;
+            record_change(conn, task.id, Some(user.id), ChangeKind::Updated, diff_tasks(Some(&existing), &task)?, batch).await?;
+            archived += 1;
+        }
+        Ok(archived)
+    }.scope_boxed())
+    .await
+    .map_err(ServerFnError::from)
+}
+
+/// Change log of a task, oldest first.
+#[server]
+pub async fn get_task_history(task_id: Id) -> Result<Vec<TaskHistoryEntry>, ServerFnError> {
+    use super::schema::users;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = load_task(&mut conn, task_id).await?;
+    require_task_role(&mut conn, &task, &user, MemberRole::Viewer).await?;
+
+    let changes = task_changes_of(&mut conn, task_id).await?;
+    let actors = users::table
+        .select(User::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let history = changes
+        .into_iter()
+        .map(|change| {
+            let actor = actors.iter().find(|u| Some(u.id) == change.actor_id).map(|u| u.name.clone());
+            TaskHistoryEntry { change, actor }
+        })
+        .collect();
+
+    Ok(history)
+}
+
+/// Restores a task to the version it had right after the given change.
+///
+/// Every later change is undone field by field, and the revert is itself recorded as a change.
+#[server]
+pub async fn revert_task(change_id: Id) -> Result<Task, ServerFnError> {
+    use super::schema::task_changes;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let target = task_changes::table
+        .find(change_id)
+        .select(TaskChange::as_select())
+        .first(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let task = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let existing = load_task(conn, target.task_id).await?;
+        require_task_role(conn, &existing, &user, MemberRole::Editor).await?;
+
+        let mut reverted = existing.clone();
+        for change in task_changes_of(conn, target.task_id).await?.iter().rev() {
+            if change.id == target.id {
+                break;
+            }
+            if change.undone_at.is_some() {
+                continue;
+            }
+            reverted = apply_changes(&reverted, &change.changes.0, true)?;
+        }
+        require_task_role(conn, &reverted, &user, MemberRole::Editor).await?;
+
+        let task = store_task(conn, reverted).await?;
+
+        let changes = diff_tasks(Some(&existing), &task)?;
+        record_change(conn, task.id, Some(user.id), ChangeKind::Reverted, changes, new_batch()).await?;
+        Ok(task)
+    }.scope_boxed())
+    .await?;
+
+    Ok(task)
+}
+
+/// Undoes the most recent action of the current user, with all the changes it made, returning
+/// the tasks as they are now. Nothing is undone if any of the tasks can't be changed back.
+#[server]
+pub async fn undo_last_change() -> Result<Vec<Task>, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let Some(last) = last_active_change(&mut conn, user.id).await? else {
+        return Ok(vec![]);
+    };
+
+    let undone = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let now = utc_now();
+        let mut undone = vec![];
+        // Newest first, so a task changed twice in the batch ends up as it was before both
+        for change in batch_changes(conn, user.id, last.batch_id).await?.into_iter().rev() {
+            if change.undone_at.is_some() {
+                continue;
+            }
+            let existing = load_task(conn, change.task_id).await?;
+            require_task_role(conn, &existing, &user, MemberRole::Editor).await?;
+            undone.push(store_task(conn, task_for_undo(&existing, &change, true)?).await?);
+            set_undone(conn, change.id, Some(now)).await?;
+        }
+        Ok(undone)
+    }.scope_boxed())
+    .await?;
+
+    Ok(undone)
+}
+
+/// Reapplies the action undone last by the current user, if nothing was changed since.
+#[server]
+pub async fn redo_last_change() -> Result<Vec<Task>, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let Some(next) = next_redo_change(&mut conn, user.id).await? else {
+        return Ok(vec![]);
+    };
+
+    let redone = conn.transaction::<_, TransactionError, _>(|conn| async move {
+        let mut redone = vec![];
+        for change in batch_changes(conn, user.id, next.batch_id).await? {
+            if change.undone_at.is_none() {
+                continue;
+            }
+            let existing = load_task(conn, change.task_id).await?;
+            require_task_role(conn, &existing, &user, MemberRole::Editor).await?;
+            redone.push(store_task(conn, task_for_undo(&existing, &change, false)?).await?);
+            set_undone(conn, change.id, None).await?;
+        }
+        Ok(redone)
+    }.scope_boxed())
+    .await?;
+
+    Ok(redone)
+}
+
+/// The user's own capacities.
+#[server]
+pub async fn get_capacities() -> Result<Vec<Capacity>, ServerFnError> {
+    use super::schema::capacities::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let capacityvec = capacities
+        .filter(user_id.eq(user.id))
+        .select(Capacity::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(capacityvec)
+}
+
+/// Sets the capacity for every occurrence of a weekday, counted in days from Monday.
+#[server]
+pub async fn set_weekday_capacity(day: i32, capacity_minutes: i32) -> Result<Capacity, ServerFnError> {
+    use super::schema::capacities::dsl::*;
+
+    let mut validator = Validator::new();
+    validator.number("day", Some(day), 0..=6);
+    validator.number("capacity_minutes", Some(capacity_minutes), 0..=DAY_MINUTES);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let capacity = Capacity {
+        id: Id(Uuid::now_v7()),
+        user_id: user.id,
+        weekday: Some(day),
+        date: None,
+        minutes: capacity_minutes,
+    };
+
+    conn.transaction(|conn| async move {
+        diesel::delete(capacities.filter(user_id.eq(user.id)).filter(weekday.eq(day)))
+            .execute(conn)
+            .await?;
+        diesel::insert_into(capacities)
+            .values(&capacity)
+            .execute(conn)
+            .await?;
+        Ok::<_, diesel::result::Error>(capacity)
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Overrides the capacity of a single date; `None` falls back to the weekday capacity again.
+#[server]
+pub async fn set_date_capacity(day: NaiveDate, capacity_minutes: Option<i32>) -> Result<(), ServerFnError> {
+    use super::schema::capacities::dsl::*;
+
+    let mut validator = Validator::new();
+    validator.date("day", Some(day));
+    validator.number("capacity_minutes", capacity_minutes, 0..=DAY_MINUTES);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    conn.transaction(|conn| async move {
+        diesel::delete(capacities.filter(user_id.eq(user.id)).filter(date.eq(day)))
+            .execute(conn)
+            .await?;
+        if let Some(capacity_minutes) = capacity_minutes {
+            diesel::insert_into(capacities)
+                .values(&Capacity {
+                    id: Id(Uuid::now_v7()),
+                    user_id: user.id,
+                    weekday: None,
+                    date: Some(day),
+                    minutes: capacity_minutes,
+                })
+                .execute(conn)
+                .await?;
+        }
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Suggests moving tasks from overloaded days to lighter ones in the week starting at `week_start`.
+#[server]
+pub async fn suggest_rebalance(week_start: NaiveDate) -> Result<Vec<RebalanceSuggestion>, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+    use super::schema::capacities;
+
+    let mut validator = Validator::new();
+    validator.date("week_start", Some(week_start));
+    validator.finish()?;
+
+    let days: Vec<NaiveDate> = (0..7).filter_map(|i| week_start.checked_add_days(Days::new(i))).collect();
+    let week_end = days.last().copied().unwrap_or(week_start);
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    // Scheduled backlog tasks take up the day as much as inbox ones
+    let visible = active_backlog_ids(&mut conn, &user).await?;
+    let taskvec = tasks
+        .select(Task::as_select())
+        .filter(deleted_at.is_null().and(archived_at.is_null()))
+        .filter(backlog_id.is_null().and(owner_id.eq(user.id)).or(backlog_id.eq_any(visible)))
+        .filter(scheduled_date.between(week_start, week_end))
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let capacityvec = capacities::table
+        .filter(capacities::user_id.eq(user.id))
+        .select(Capacity::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let today = preferences_of(&mut conn, &user).await?.today();
+    Ok(rebalance(&days, &taskvec, &capacityvec, today))
+}
+
+/// iCalendar export of the tasks scheduled between `start` and `end`, inclusive.
+#[server]
+pub async fn export_ics(start: NaiveDate, end: NaiveDate) -> Result<String, ServerFnError> {
+    use super::schema::tasks::dsl::*;
+
+    let mut validator = Validator::new();
+    validator.date_range("end", start, end);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let taskvec = tasks
+        .select(Task::as_select())
+        .filter(deleted_at.is_null())
+        .filter(scheduled_date.between(start, end))
+        .order((scheduled_date.asc(), start_time.asc()))
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let mut visible = vec![];
+    for task in taskvec {
+        if require_task_role(&mut conn, &task, &user, MemberRole::Viewer).await.is_ok() {
+            visible.push(task);
+        }
+    }
+
+    let preferences = preferences_of(&mut conn, &user).await?;
+    let name = format!("Planner {} – {}", preferences.format_date(start), preferences.format_date(end));
+    Ok(to_ics(&visible, utc_now(), &name))
+}
+
+/// The user the browser is signed in as, or `None` when it has to sign in first.
+#[server]
+pub async fn signed_in_user() -> Result<Option<User>, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    match current_user(&mut conn).await {
+        Ok(user) => Ok(Some(user)),
+        Err(e) if e.to_string().contains(NOT_SIGNED_IN) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Signs the browser in with a session token made by `planner sign-in <name>`.
+#[server]
+pub async fn sign_in(token: String) -> Result<User, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    let token = token.trim();
+    let user = session_user(&mut conn, token)
+        .await?
+        .ok_or_else(|| ServerFnError::new("Unknown or expired sign-in token"))?;
+    set_session_cookie(token)?;
+
+    Ok(user)
+}
+
+/// Ends the session of the browser.
+#[server]
+pub async fn sign_out() -> Result<(), ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    if let Some(token) = request_session_token().await {
+        delete_session(&mut conn, &token).await?;
+    }
+    set_session_cookie("")
+}
+
+/// Starts a session for the user named `user_name` from the command line, creating the user if
+/// needed, and returns the token to sign in with.
+#[cfg(feature = "server")]
+pub(super) async fn command_line_session(user_name: &str) -> Result<String, ServerFnError> {
+    let mut validator = Validator::new();
+    let user_name = validator.text("name", user_name, NAME_MAX);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = find_or_create_user(&mut conn, &user_name).await?;
+
+    Ok(create_session(&mut conn, &user).await?.token)
+}
+
+/// The preferences of `user`, or the defaults if they never changed any.
+#[cfg(feature = "server")]
+async fn preferences_of(conn: &mut DbConnection, user: &User) -> Result<Preferences, ServerFnError> {
+    use super::schema::user_preferences::dsl::*;
+
+    let stored = user_preferences
+        .find(user.id)
+        .select(UserPreferences::as_select())
+        .first(conn)
+        .await
+        .optional()
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(stored.map(|stored| stored.preferences).unwrap_or_default())
+}
+
+#[server]
+pub async fn get_preferences() -> Result<Preferences, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    preferences_of(&mut conn, &user).await
+}
+
+#[server]
+pub async fn set_preferences(new_preferences: Preferences) -> Result<Preferences, ServerFnError> {
+    use super::schema::user_preferences::dsl::*;
+
+    let mut validator = Validator::new();
+    validator.preferences(&new_preferences);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    diesel::insert_into(user_preferences)
+        .values(&UserPreferences { user_id: user.id, preferences: new_preferences.clone() })
+        .on_conflict(user_id)
+        .do_update()
+        .set(preferences.eq(&new_preferences))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
+
+    Ok(new_preferences)
+}
+
+/// Rolls over open tasks from past days the current user can edit, using the configured policy.
+/// Past days are those before today in the user's time zone.
+#[server]
+pub async fn rollover_tasks() -> Result<RolloverReport, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let today = preferences_of(&mut conn, &user).await?.today();
+    roll_over(&mut conn, configured_policy(), today, &user).await
+}
+
+/// Every user with their preferences, the defaults for those who never changed any.
+#[cfg(feature = "server")]
+pub(super) async fn users_with_preferences() -> Result<Vec<(User, Preferences)>, ServerFnError> {
+    use super::schema::{users, user_preferences};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    let rows = users::table
+        .left_join(user_preferences::table)
+        .select((User::as_select(), user_preferences::preferences.nullable()))
+        .load::<(User, Option<Preferences>)>(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(rows.into_iter().map(|(user, stored)| (user, stored.unwrap_or_default())).collect())
+}
+
+/// Rolls over, on behalf of each of `users`, the tasks they can edit from before `today`, the
+/// date it now is where they are.
+#[cfg(feature = "server")]
+pub(super) async fn scheduled_rollover(users: &[User], today: NaiveDate) -> Result<RolloverReport, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    let policy = configured_policy();
+    let mut report = RolloverReport { policy, rolled_over: 0 };
+    for user in users {
+        report.rolled_over += roll_over(&mut conn, policy, today, user).await?.rolled_over;
+    }
+    Ok(report)
+}
+
+/// Records that `blocker_id` has to be done before `blocked_id`, unless that would make a task
+/// wait for itself.
+#[server]
+pub async fn add_task_dependency(blocker_id: Id, blocked_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::task_dependencies;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let blocker = load_task(&mut conn, blocker_id).await?;
+    let blocked = load_task(&mut conn, blocked_id).await?;
+    require_task_role(&mut conn, &blocker, &user, MemberRole::Viewer).await?;
+    require_task_role(&mut conn, &blocked, &user, MemberRole::Editor).await?;
+
+    let existing = task_dependencies::table
+        .select(TaskDependency::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    if creates_cycle(&existing, blocker_id, blocked_id) {
+        return Err(ServerFnError::new(format!("\"{}\" already waits for \"{}\"", blocker.title, blocked.title)));
+    }
+
+    diesel::insert_into(task_dependencies::table)
+        .values(&TaskDependency { blocker_id, blocked_id })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    Ok(())
+}
+
+#[server]
+pub async fn remove_task_dependency(blocker_id: Id, blocked_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::task_dependencies;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let blocked = load_task(&mut conn, blocked_id).await?;
+    require_task_role(&mut conn, &blocked, &user, MemberRole::Editor).await?;
+
+    diesel::delete(task_dependencies::table.find((blocker_id, blocked_id)))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
+
+    Ok(())
+}
+
+/// Tasks linked to a task in either direction, leaving out deleted ones and ones `user` can't see.
+#[cfg(feature = "server")]
+async fn load_task_links(conn: &mut DbConnection, task_id: Id, user: &User) -> Result<TaskLinks, ServerFnError> {
+    use super::schema::{task_dependencies, tasks};
+
+    let dependencies = task_dependencies::table
+        .filter(task_dependencies::blocker_id.eq(task_id).or(task_dependencies::blocked_id.eq(task_id)))
+        .select(TaskDependency::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let linked_ids: Vec<Id> = dependencies
+        .iter()
+        .map(|d| if d.blocker_id == task_id { d.blocked_id } else { d.blocker_id })
+        .collect();
+    let linked = tasks::table
+        .filter(tasks::id.eq_any(linked_ids))
+        .filter(tasks::deleted_at.is_null())
+        .select(Task::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let mut links = TaskLinks::default();
+    for task in linked {
+        if require_task_role(conn, &task, user, MemberRole::Viewer).await.is_err() {
+            continue;
+        }
+        if dependencies.iter().any(|d| d.blocker_id == task.id) {
+            links.blocked_by.push(task);
+        } else {
+            links.blocks.push(task);
+        }
+    }
+    Ok(links)
+}
+
+#[server]
+pub async fn get_task_links(task_id: Id) -> Result<TaskLinks, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = load_task(&mut conn, task_id).await?;
+    require_task_role(&mut conn, &task, &user, MemberRole::Viewer).await?;
+
+    load_task_links(&mut conn, task_id, &user).await
+}
+
+/// Which of the given tasks still wait for an open task. Completing the last open blocker of a
+/// task therefore unblocks it.
+#[server]
+pub async fn get_blocked_tasks(task_ids: Vec<Id>) -> Result<Vec<Id>, ServerFnError> {
+    use super::schema::{task_dependencies, tasks};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    // Only tasks the user can see, the same as in query_tasks
+    let visible = visible_backlog_ids(&mut conn, &user).await?;
+    let visible_tasks = tasks::table
+        .filter(tasks::id.eq_any(task_ids))
+        .filter(tasks::backlog_id.is_null().and(tasks::owner_id.eq(user.id)).or(tasks::backlog_id.eq_any(visible)))
+        .select(tasks::id);
+    let open_tasks = tasks::table
+        .filter(tasks::completed.eq(false))
+        .filter(tasks::deleted_at.is_null())
+        .select(tasks::id);
+
+    task_dependencies::table
+        .filter(task_dependencies::blocked_id.eq_any(visible_tasks))
+        .filter(task_dependencies::blocker_id.eq_any(open_tasks))
+        .select(task_dependencies::blocked_id)
+        .distinct()
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Warnings about the task's date conflicting with the tasks it is linked to.
+#[server]
+pub async fn get_schedule_warnings(task_id: Id) -> Result<Vec<String>, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = load_task(&mut conn, task_id).await?;
+    require_task_role(&mut conn, &task, &user, MemberRole::Viewer).await?;
+    let links = load_task_links(&mut conn, task_id, &user).await?;
+
+    Ok(schedule_warnings(&task, &links))
+}
+
+#[server]
+pub async fn create_tag(name: String, color: Option<String>) -> Result<Tag, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    current_user(&mut conn).await?;
+
+    insert_tag(&mut conn, name, color).await
+}
+
+#[cfg(feature = "server")]
+async fn insert_tag(conn: &mut DbConnection, name: String, color: Option<String>) -> Result<Tag, ServerFnError> {
+    use super::schema::tags;
+
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    if let Some(color) = &color {
+        validator.color("color", color);
+    }
+    if find_tag_by_name(conn, &name).await?.is_some() {
+        validator.error("name", "is already taken by another tag");
+    }
+    validator.finish()?;
+
+    let color = match color {
+        Some(color) => color,
+        None => {
+            let count: i64 = tags::table
+                .count()
+                .get_result(conn)
+                .await
+                .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+            TAG_COLORS[count as usize % TAG_COLORS.len()].to_string()
+        },
+    };
+
+    let new_tag = Tag {
+        id: Id(Uuid::now_v7()),
+        name,
+        color,
+    };
+
+    diesel::insert_into(tags::table)
+        .values(&new_tag)
+        .execute(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+    Ok(new_tag)
+}
+
+/// The tag with the given name, see `Tag::is_named`.
+#[cfg(feature = "server")]
+async fn find_tag_by_name(conn: &mut DbConnection, tag_name: &str) -> Result<Option<Tag>, ServerFnError> {
+    use super::schema::tags::dsl::*;
+
+    let candidates = tags
+        .select(Tag::as_select())
+        .load(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(candidates.into_iter().find(|tag| tag.is_named(tag_name)))
+}
+
+#[server]
+pub async fn get_tags() -> Result<Vec<Tag>, ServerFnError> {
+    use super::schema::tags::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    current_user(&mut conn).await?;
+
+    let tagvec = tags
+        .order(name.asc())
+        .select(Tag::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(tagvec)
+}
+
+#[server]
+pub async fn update_tag(tag: Tag) -> Result<Tag, ServerFnError> {
+    use super::schema::tags::dsl::*;
+
+    let mut validator = Validator::new();
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    current_user(&mut conn).await?;
+
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &tag.name, NAME_MAX);
+    validator.color("color", &tag.color);
+    if find_tag_by_name(&mut conn, &new_name).await?.is_some_and(|other| other.id != tag.id) {
+        validator.error("name", "is already taken by another tag");
+    }
+    validator.finish()?;
+
+    diesel::update(tags.find(tag.id))
+        .set((name.eq(new_name), color.eq(tag.color)))
+        .returning(Tag::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Deletes a tag and takes it off every task carrying it.
+#[server]
+pub async fn delete_tag(tag_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::{tags, task_tags};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    current_user(&mut conn).await?;
+
+    conn.transaction(|conn| async move {
+        diesel::delete(task_tags::table.filter(task_tags::tag_id.eq(tag_id)))
+            .execute(conn)
+            .await?;
+        diesel::delete(tags::table.find(tag_id))
+            .execute(conn)
+            .await?;
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))
+}
+
+/// Which of the given tasks carry which tags, leaving out the tasks the user can't see.
+#[server]
+pub async fn get_task_tags(task_ids: Vec<Id>) -> Result<Vec<TaskTag>, ServerFnError> {
+    use super::schema::{task_tags, tasks};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let taskvec = tasks::table
+        .filter(tasks::id.eq_any(task_ids))
+        .select(Task::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    let mut visible = vec![];
+    for task in taskvec {
+        if require_task_role(&mut conn, &task, &user, MemberRole::Viewer).await.is_ok() {
+            visible.push(task.id);
+        }
+    }
+
+    let tagged = task_tags::table
+        .filter(task_tags::task_id.eq_any(visible))
+        .select(TaskTag::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(tagged)
+}
+
+/// Puts a tag on a task by name, creating the tag if it doesn't exist yet.
+#[server]
+pub async fn add_task_tag(task_id: Id, tag_name: String) -> Result<Tag, ServerFnError> {
+    use super::schema::task_tags;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = load_task(&mut conn, task_id).await?;
+    require_task_role(&mut conn, &task, &user, MemberRole::Editor).await?;
+
+    let tag = match find_tag_by_name(&mut conn, &tag_name).await? {
+        Some(tag) => tag,
+        None => insert_tag(&mut conn, tag_name, None).await?,
+    };
+
+    diesel::insert_into(task_tags::table)
+        .values(&TaskTag { task_id, tag_id: tag.id })
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    Ok(tag)
+}
+
+#[server]
+pub async fn remove_task_tag(task_id: Id, tag_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::task_tags;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let task = load_task(&mut conn, task_id).await?;
+    require_task_role(&mut conn, &task, &user, MemberRole::Editor).await?;
+
+    diesel::delete(task_tags::table.find((task_id, tag_id)))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
+
+    Ok(())
+}
+
+#[server]
+pub async fn create_saved_view(name: String, filter: TaskFilter, upcoming_days: Option<i32>, query: Option<String>) -> Result<SavedView, ServerFnError> {
+    use super::schema::saved_views;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let mut validator = Validator::new();
+    let new_view = validator.saved_view(SavedView {
+        id: Id(Uuid::now_v7()),
+        owner_id: user.id,
+        name,
+        filter,
+        upcoming_days,
+        query,
+    });
+    validator.finish()?;
+
+    diesel::insert_into(saved_views::table)
+        .values(&new_view)
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+    Ok(new_view)
+}
+
+/// Views saved by the current user, oldest first.
+#[server]
+pub async fn get_saved_views() -> Result<Vec<SavedView>, ServerFnError> {
+    use super::schema::saved_views::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let views = saved_views
+        .filter(owner_id.eq(user.id))
+        .order(id.asc())
+        .select(SavedView::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(views)
+}
+
+#[server]
+pub async fn update_saved_view(view: SavedView) -> Result<SavedView, ServerFnError> {
+    use super::schema::saved_views::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let mut validator = Validator::new();
+    let view = validator.saved_view(view);
+    validator.finish()?;
+
+    // Only the owner's own views match, others are reported as missing
+    diesel::update(saved_views.filter(id.eq(view.id)).filter(owner_id.eq(user.id)))
+        .set((name.eq(view.name), filter.eq(view.filter), upcoming_days.eq(view.upcoming_days), query.eq(view.query)))
+        .returning(SavedView::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+#[server]
+pub async fn delete_saved_view(view_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::saved_views::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    diesel::delete(saved_views.filter(id.eq(view_id)).filter(owner_id.eq(user.id)))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
+
+    Ok(())
+}
+
+#[server]
+pub async fn create_backlog(name: String) -> Result<Backlog, ServerFnError> {
+    use super::schema::{backlogs, backlog_members};
+
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let last_position: Option<i32> = backlogs::table
+        .select(diesel::dsl::max(backlogs::position))
+        .first(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let new_backlog = Backlog {
+        id: Id(Uuid::now_v7()),
+        name,
+        project_id: None,
+        position: last_position.map_or(0, |p| p + 1),
+        archived_at: None,
+        deleted_at: None,
+    };
+
+    // The creator owns the backlog; it stays private until more members are added.
+    let owner = BacklogMember {
+        backlog_id: new_backlog.id,
+        user_id: user.id,
+        role: MemberRole::Owner,
+    };
+
+    conn.transaction(|conn| async move {
+        diesel::insert_into(backlogs::table)
+            .values(&new_backlog)
+            .execute(conn)
+            .await?;
+        diesel::insert_into(backlog_members::table)
+            .values(&owner)
+            .execute(conn)
+            .await?;
+        Ok::<_, diesel::result::Error>(new_backlog)
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))
+}
+
+/// Backlogs visible to the user in order, archived ones included.
+#[server]
+pub async fn get_backlogs() -> Result<Vec<Backlog>, ServerFnError> {
+    use super::schema::backlogs::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+
+    let mut backlogvec = backlogs
+        .filter(deleted_at.is_null())
+        .order((position.asc(), id.asc()))
+        .select(Backlog::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let visible = visible_backlog_ids(&mut conn, &user).await?;
+    backlogvec.retain(|backlog| visible.contains(&backlog.id));
+
+    Ok(backlogvec)
+}
+
+#[server]
+pub async fn update_backlog(backlog: Backlog) -> Result<(), ServerFnError> {
+    use super::schema::backlogs::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog.id, &user, MemberRole::Editor).await?;
+    if let Some(project) = backlog.project_id {
+        require_project_visible(&mut conn, project, &user).await?;
+    }
+
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &backlog.name, NAME_MAX);
+    validator.finish()?;
+
+    diesel::update(backlogs.find(backlog.id))
+        .set((name.eq(new_name), project_id.eq(backlog.project_id)))
+        .returning(Backlog::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(())
+}
+
+/// Removes a backlog. Its tasks are moved elsewhere, deleted along with it, or kept with it in
+/// the archive, as `removal` says. The backlog row itself stays so history can still refer to it.
+#[server]
+pub async fn delete_backlog(backlog_id: Id, removal: BacklogRemoval) -> Result<(), ServerFnError> {
+    use super::schema::{backlogs, tasks};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog_id, &user, MemberRole::Owner).await?;
+
+    let now = utc_now();
+    if removal == BacklogRemoval::Archive {
+        diesel::update(backlogs::table.find(backlog_id))
+            .set(backlogs::archived_at.eq(now))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
+        return Ok(());
+    }
+
+    if let BacklogRemoval::MoveTasks(Some(target)) = removal {
+        if target == backlog_id {
+            return Err(ServerFnError::new("Tasks can't be moved to the backlog being deleted"));
+        }
+        require_backlog_role(&mut conn, target, &user, MemberRole::Editor).await?;
+    }
+
+    let backlog_tasks = tasks::table
+        .filter(tasks::backlog_id.eq(backlog_id))
+        .filter(tasks::deleted_at.is_null())
+        .select(Task::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    // Each task gets its own history entry, in one batch so a single undo brings them all back.
+    // All in one transaction, so a failure leaves the backlog and its tasks as they were.
+    let batch = new_batch();
+    conn.transaction::<_, TransactionError, _>(|conn| async move {
+        for existing in backlog_tasks {
+            let (task, kind) = match removal {
+                // Tasks moved to the inbox land in the inbox of whoever deleted the backlog
+                BacklogRemoval::MoveTasks(None) => (Task { backlog_id: None, status_id: None, owner_id: Some(user.id), ..existing.clone() }, ChangeKind::Updated),
+                BacklogRemoval::MoveTasks(target) => (Task { backlog_id: target, status_id: None, ..existing.clone() }, ChangeKind::Updated),
+                _ => (Task { deleted_at: Some(now), ..existing.clone() }, ChangeKind::Deleted),
+            };
+            let task = store_task(conn, task).await?;
+            record_change(conn, task.id, Some(user.id), kind, diff_tasks(Some(&existing), &task)?, batch).await?;
+        }
+
+        diesel::update(backlogs::table.find(backlog_id))
+            .set(backlogs::deleted_at.eq(now))
+            .execute(conn)
+            .await?;
+        Ok(())
+    }.scope_boxed())
+    .await?;
+
+    Ok(())
+}
+
+/// Brings an archived backlog back onto the board.
+#[server]
+pub async fn restore_backlog(backlog_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::backlogs::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog_id, &user, MemberRole::Owner).await?;
 
-#[cfg(feature = "server")]
-static DB_MUTEX: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+    diesel::update(backlogs.find(backlog_id).filter(deleted_at.is_null()))
+        .set(archived_at.eq(None::<chrono::NaiveDateTime>))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))?;
 
-#[cfg(feature = "server")]
-async fn get_db_connection() -> Result<SyncConnectionWrapper<SqliteConnection>, ConnectionError> {
-    dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    SyncConnectionWrapper::<SqliteConnection>::establish(&database_url).await
+    Ok(())
 }
 
+/// Stores the order of the backlogs as given by `backlog_ids`.
 #[server]
-pub async fn create_task(title: String, date: Option<NaiveDate>, backlog_id: Option<Id>) -> Result<Task, ServerFnError> {
-    use super::schema::tasks;
+pub async fn reorder_backlogs(backlog_ids: Vec<Id>) -> Result<(), ServerFnError> {
+    use super::schema::backlogs::dsl::*;
 
-    let mut new_task = Task {
-        id: Id(Uuid::now_v7()),
-        title: title,
-        important: false,
-        urgent: false,
-        content: None,
-        completed: false,
-        role_id: None,
-        backlog_id: None,
-        scheduled_date: None,
-        created_at: Utc::now().naive_utc(),
-        updated_at: None,
-        deleted_at: None,
-    };
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
 
-    if let Some(date) = date {
-        new_task.scheduled_date = Some(date);
-    }
-    if let Some(backlog_id) = backlog_id {
-        new_task.backlog_id = Some(backlog_id);
+    // Positions are shared with the other members of each backlog, so moving one takes edit access
+    for backlog_id in &backlog_ids {
+        require_backlog_role(&mut conn, *backlog_id, &user, MemberRole::Editor).await?;
     }
+    conn.transaction(|conn| async move {
+        for (index, backlog_id) in backlog_ids.into_iter().enumerate() {
+            diesel::update(backlogs.find(backlog_id))
+                .set(position.eq(index as i32))
+                .execute(conn)
+                .await?;
+        }
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Status columns of a backlog's board in order. Empty when the backlog is a plain list.
+#[server]
+pub async fn get_backlog_columns(backlog: Id) -> Result<Vec<BacklogColumn>, ServerFnError> {
+    use super::schema::backlog_columns::dsl::*;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog, &user, MemberRole::Viewer).await?;
 
-    diesel::insert_into(tasks::table)
-        .values(&new_task)
+    backlog_columns
+        .filter(backlog_id.eq(backlog))
+        .order(position.asc())
+        .select(BacklogColumn::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
+}
+
+/// Adds a column to the right of the existing ones.
+#[server]
+pub async fn create_backlog_column(backlog: Id, name: String, wip_limit: Option<i32>) -> Result<BacklogColumn, ServerFnError> {
+    use super::schema::backlog_columns;
+
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    validator.number("wip_limit", wip_limit, 1..=999);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog, &user, MemberRole::Editor).await?;
+
+    let last_position: Option<i32> = backlog_columns::table
+        .filter(backlog_columns::backlog_id.eq(backlog))
+        .select(diesel::dsl::max(backlog_columns::position))
+        .first(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let column = BacklogColumn {
+        id: Id(Uuid::now_v7()),
+        backlog_id: backlog,
+        name,
+        position: last_position.map_or(0, |p| p + 1),
+        wip_limit,
+    };
+
+    diesel::insert_into(backlog_columns::table)
+        .values(&column)
         .execute(&mut conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+    Ok(column)
+}
 
-    Ok(new_task)
+/// Renames a column or changes its WIP limit.
+#[server]
+pub async fn update_backlog_column(column: BacklogColumn) -> Result<BacklogColumn, ServerFnError> {
+    use super::schema::backlog_columns::dsl::*;
+
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &column.name, NAME_MAX);
+    validator.number("wip_limit", column.wip_limit, 1..=999);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, column.backlog_id, &user, MemberRole::Editor).await?;
+
+    diesel::update(backlog_columns.find(column.id).filter(backlog_id.eq(column.backlog_id)))
+        .set((name.eq(new_name), wip_limit.eq(column.wip_limit)))
+        .returning(BacklogColumn::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
 }
 
+/// Deletes a column. Its tasks stay in the backlog and show up in the first column.
 #[server]
-pub async fn get_tasks(filter: TaskFilter) -> Result<Vec<Task>, ServerFnError> {
-    use super::schema::tasks::dsl::*;
+pub async fn delete_backlog_column(column_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::{backlog_columns, tasks};
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
 
-    match filter.scheduled_date {
-        Some(date) => {
-            let taskvec = tasks
-                .select(Task::as_select())
-                .filter(deleted_at.is_null().and(scheduled_date.eq(date)))
-                .load(&mut conn)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    let column = backlog_columns::table
+        .find(column_id)
+        .select(BacklogColumn::as_select())
+        .first(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    require_backlog_role(&mut conn, column.backlog_id, &user, MemberRole::Editor).await?;
 
-            Ok(taskvec)
-        },
-        None => {
-            let taskvec = tasks
-                .select(Task::as_select())
-                .filter(deleted_at.is_null().and(scheduled_date.is_null()))
-                .load(&mut conn)
-                .await
-                .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+    conn.transaction(|conn| async move {
+        diesel::update(tasks::table.filter(tasks::status_id.eq(column_id)))
+            .set(tasks::status_id.eq(None::<Id>))
+            .execute(conn)
+            .await?;
+        diesel::delete(backlog_columns::table.find(column_id))
+            .execute(conn)
+            .await?;
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))
+}
 
-            Ok(taskvec)
+/// Stores the order of a backlog's columns as given by `column_ids`.
+#[server]
+pub async fn reorder_backlog_columns(backlog: Id, column_ids: Vec<Id>) -> Result<(), ServerFnError> {
+    use super::schema::backlog_columns::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog, &user, MemberRole::Editor).await?;
+
+    conn.transaction(|conn| async move {
+        for (index, column_id) in column_ids.into_iter().enumerate() {
+            diesel::update(backlog_columns.find(column_id).filter(backlog_id.eq(backlog)))
+                .set(position.eq(index as i32))
+                .execute(conn)
+                .await?;
         }
-    }
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+#[server]
+pub async fn get_current_user() -> Result<User, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
 
+    current_user(&mut conn).await
 }
 
 #[server]
-pub async fn update_task(task: Task) -> Result<Task, ServerFnError> {
-    use super::schema::tasks::dsl::*;
+pub async fn get_backlog_members(backlog_id: Id) -> Result<Vec<Member>, ServerFnError> {
+    use super::schema::{backlog_members, users};
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog_id, &user, MemberRole::Viewer).await?;
 
-    let task = diesel::update(tasks.find(task.id))
-        .set((
-            title.eq(task.title), 
-            important.eq(task.important),
-            urgent.eq(task.urgent),
-            content.eq(task.content),
-            completed.eq(task.completed),
-            role_id.eq(task.role_id),
-            backlog_id.eq(task.backlog_id),
-            scheduled_date.eq(task.scheduled_date),
-            updated_at.eq(Utc::now().naive_utc())))
-        .returning(Task::as_returning())
-        .get_result(&mut conn)
+    let members = backlog_members::table
+        .inner_join(users::table)
+        .filter(backlog_members::backlog_id.eq(backlog_id))
+        .select((User::as_select(), backlog_members::role))
+        .load::<(User, MemberRole)>(&mut conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
 
-    Ok(task)
+    Ok(members.into_iter().map(|(user, role)| Member { user, role }).collect())
 }
 
+/// Adds a user to a backlog by name, or changes the role of an existing member.
 #[server]
-pub async fn delete_task(task_id: Id) -> Result<(), ServerFnError> {
-    use super::schema::tasks::dsl::*;
+pub async fn set_backlog_member(backlog_id: Id, user_name: String, role: MemberRole) -> Result<Member, ServerFnError> {
+    use super::schema::backlog_members;
+
+    let mut validator = Validator::new();
+    let user_name = validator.text("user_name", &user_name, NAME_MAX);
+    validator.finish()?;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog_id, &user, MemberRole::Owner).await?;
 
-    diesel::update(tasks.find(task_id))
-        .set(deleted_at.eq(Utc::now().naive_utc()))
-        .returning(Task::as_returning())
-        .get_result(&mut conn)
+    let member_user = find_or_create_user(&mut conn, &user_name).await?;
+    ensure_owner_remains(&mut conn, backlog_id, member_user.id, Some(role)).await?;
+
+    diesel::insert_into(backlog_members::table)
+        .values(&BacklogMember { backlog_id, user_id: member_user.id, role })
+        .on_conflict((backlog_members::backlog_id, backlog_members::user_id))
+        .do_update()
+        .set(backlog_members::role.eq(role))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+
+    Ok(Member { user: member_user, role })
+}
+
+#[server]
+pub async fn remove_backlog_member(backlog_id: Id, user_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::backlog_members;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_backlog_role(&mut conn, backlog_id, &user, MemberRole::Owner).await?;
+    ensure_owner_remains(&mut conn, backlog_id, user_id, None).await?;
+
+    diesel::delete(backlog_members::table
+        .filter(backlog_members::backlog_id.eq(backlog_id))
+        .filter(backlog_members::user_id.eq(user_id)))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
+
+    Ok(())
+}
+
+/// Rejects a membership change that would leave a shared backlog without an owner.
+#[cfg(feature = "server")]
+async fn ensure_owner_remains(conn: &mut DbConnection, backlog: Id, changed_user: Id, new_role: Option<MemberRole>) -> Result<(), ServerFnError> {
+    use super::schema::backlog_members::dsl::*;
+
+    if new_role == Some(MemberRole::Owner) {
+        return Ok(());
+    }
+
+    let other_owners = backlog_members
+        .filter(backlog_id.eq(backlog))
+        .filter(role.eq(MemberRole::Owner))
+        .filter(user_id.ne(changed_user))
+        .count()
+        .get_result::<i64>(conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
 
+    if other_owners == 0 {
+        return Err(ServerFnError::new("A shared backlog needs at least one owner"));
+    }
+
     Ok(())
 }
 
 #[server]
-pub async fn create_backlog(name: String) -> Result<Backlog, ServerFnError> {
-    use super::schema::backlogs;
+pub async fn create_project(name: String) -> Result<Project, ServerFnError> {
+    use super::schema::projects;
 
-    let new_backlog = Backlog {
-        id: Id(Uuid::now_v7()),
-        name
-    };
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    validator.finish()?;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
 
-    diesel::insert_into(backlogs::table)
-        .values(&new_backlog)
+    let new_project = Project {
+        id: Id(Uuid::now_v7()),
+        name,
+        description: None,
+        status: ProjectStatus::default(),
+        target_date: None,
+        owner_id: Some(user.id),
+    };
+
+    diesel::insert_into(projects::table)
+        .values(&new_project)
         .execute(&mut conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
-    Ok(new_backlog)
+    Ok(new_project)
 }
 
+/// Projects the user owns or has a backlog in.
 #[server]
-pub async fn get_backlogs() -> Result<Vec<Backlog>, ServerFnError> {
-    use super::schema::backlogs::dsl::*;
+pub async fn get_projects() -> Result<Vec<Project>, ServerFnError> {
+    use super::schema::projects::dsl::*;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
 
-    let backlogvec = backlogs
-        .select(Backlog::as_select())
+    let visible = visible_project_ids(&mut conn, &user).await?;
+    let projectvec = projects
+        .filter(id.eq_any(visible))
+        .order(name.asc())
+        .select(Project::as_select())
         .load(&mut conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
 
-    Ok(backlogvec)
+    Ok(projectvec)
 }
 
 #[server]
-pub async fn update_backlog(backlog: Backlog) -> Result<(), ServerFnError> {
-    use super::schema::backlogs::dsl::*;
+pub async fn update_project(project: Project) -> Result<Project, ServerFnError> {
+    use super::schema::projects::dsl::*;
+
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &project.name, NAME_MAX);
+    let new_description = validator.optional_text("description", project.description.as_deref(), CONTENT_MAX);
+    validator.date("target_date", project.target_date);
+    validator.finish()?;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_project_owner(&mut conn, project.id, &user).await?;
 
-    diesel::update(backlogs.find(backlog.id))
-        .set(name.eq(backlog.name))
-        .returning(Backlog::as_returning())
+    diesel::update(projects.find(project.id))
+        .set((
+            name.eq(new_name),
+            description.eq(new_description),
+            status.eq(project.status),
+            target_date.eq(project.target_date)))
+        .returning(Project::as_returning())
         .get_result(&mut conn)
         .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Deletes a project with its milestones. Its backlogs and tasks stay, without a project.
+#[server]
+pub async fn delete_project(project: Id) -> Result<(), ServerFnError> {
+    use super::schema::{backlogs, milestones, projects, tasks};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_project_owner(&mut conn, project, &user).await?;
+
+    conn.transaction(|conn| async move {
+        diesel::update(backlogs::table.filter(backlogs::project_id.eq(project)))
+            .set(backlogs::project_id.eq(None::<Id>))
+            .execute(conn)
+            .await?;
+        diesel::update(tasks::table.filter(tasks::project_id.eq(project)))
+            .set(tasks::project_id.eq(None::<Id>))
+            .execute(conn)
+            .await?;
+        diesel::delete(milestones::table.filter(milestones::project_id.eq(project)))
+            .execute(conn)
+            .await?;
+        diesel::delete(projects::table.find(project))
+            .execute(conn)
+            .await?;
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))
+}
+
+/// Adds a milestone after the existing ones of the project.
+#[server]
+pub async fn create_milestone(project: Id, name: String, due_date: Option<NaiveDate>) -> Result<Milestone, ServerFnError> {
+    use super::schema::milestones;
+
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    validator.date("due_date", due_date);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_project_owner(&mut conn, project, &user).await?;
+
+    let last_position: Option<i32> = milestones::table
+        .filter(milestones::project_id.eq(project))
+        .select(diesel::dsl::max(milestones::position))
+        .first(&mut conn)
+        .await
         .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
 
-    Ok(())
+    let milestone = Milestone {
+        id: Id(Uuid::now_v7()),
+        project_id: project,
+        name,
+        due_date,
+        position: last_position.map_or(0, |p| p + 1),
+        completed: false,
+    };
+
+    diesel::insert_into(milestones::table)
+        .values(&milestone)
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database insert error: {}", e)))?;
+    Ok(milestone)
+}
+
+#[cfg(feature = "server")]
+async fn load_milestone(conn: &mut DbConnection, milestone_id: Id) -> Result<Milestone, ServerFnError> {
+    use super::schema::milestones::dsl::*;
+
+    milestones
+        .find(milestone_id)
+        .select(Milestone::as_select())
+        .first(conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))
 }
 
 #[server]
-pub async fn delete_backlog(backlog_id: Id) -> Result<(), ServerFnError> {
-    use super::schema::backlogs::dsl::*;
+pub async fn update_milestone(milestone: Milestone) -> Result<Milestone, ServerFnError> {
+    use super::schema::milestones::dsl::*;
+
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &milestone.name, NAME_MAX);
+    validator.date("due_date", milestone.due_date);
+    validator.finish()?;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    let existing = load_milestone(&mut conn, milestone.id).await?;
+    require_project_owner(&mut conn, existing.project_id, &user).await?;
+
+    diesel::update(milestones.find(milestone.id))
+        .set((name.eq(new_name), due_date.eq(milestone.due_date), completed.eq(milestone.completed)))
+        .returning(Milestone::as_returning())
+        .get_result(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+#[server]
+pub async fn delete_milestone(milestone_id: Id) -> Result<(), ServerFnError> {
+    use super::schema::milestones::dsl::*;
 
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    let existing = load_milestone(&mut conn, milestone_id).await?;
+    require_project_owner(&mut conn, existing.project_id, &user).await?;
 
-    diesel::delete(backlogs
-        .filter(id.eq(backlog_id.0.to_string())))
+    diesel::delete(milestones.find(milestone_id))
         .execute(&mut conn)
         .await
         .map_err(|e| ServerFnError::new(format!("Database delete error: {}", e)))?;
@@ -211,11 +2244,84 @@ pub async fn delete_backlog(backlog_id: Id) -> Result<(), ServerFnError> {
     Ok(())
 }
 
+/// Stores the order of a project's milestones as given by `milestone_ids`.
+#[server]
+pub async fn reorder_milestones(project: Id, milestone_ids: Vec<Id>) -> Result<(), ServerFnError> {
+    use super::schema::milestones::dsl::*;
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_project_owner(&mut conn, project, &user).await?;
+
+    conn.transaction(|conn| async move {
+        for (index, milestone_id) in milestone_ids.into_iter().enumerate() {
+            diesel::update(milestones.find(milestone_id).filter(project_id.eq(project)))
+                .set(position.eq(index as i32))
+                .execute(conn)
+                .await?;
+        }
+        Ok::<_, diesel::result::Error>(())
+    }.scope_boxed())
+    .await
+    .map_err(|e| ServerFnError::new(format!("Database update error: {}", e)))
+}
+
+/// Progress of a project over the tasks in it and in its backlogs that the user can see.
+#[server]
+pub async fn get_project_overview(project: Id) -> Result<ProjectOverview, ServerFnError> {
+    use super::schema::{backlogs, milestones, projects, tasks};
+
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+    let user = current_user(&mut conn).await?;
+    require_project_visible(&mut conn, project, &user).await?;
+
+    let found = projects::table
+        .find(project)
+        .select(Project::as_select())
+        .first(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let milestonevec = milestones::table
+        .filter(milestones::project_id.eq(project))
+        .order(milestones::position.asc())
+        .select(Milestone::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    let project_backlogs = backlogs::table
+        .filter(backlogs::project_id.eq(project))
+        .select(backlogs::id);
+    let visible = visible_backlog_ids(&mut conn, &user).await?;
+    let taskvec = tasks::table
+        .filter(tasks::deleted_at.is_null())
+        .filter(tasks::project_id.eq(project).or(tasks::backlog_id.eq_any(project_backlogs)))
+        .filter(tasks::backlog_id.is_null().and(tasks::owner_id.eq(user.id)).or(tasks::backlog_id.eq_any(visible)))
+        .select(Task::as_select())
+        .load(&mut conn)
+        .await
+        .map_err(|e| ServerFnError::new(format!("Database fetch error: {}", e)))?;
+
+    Ok(ProjectOverview {
+        project: found,
+        milestones: milestonevec,
+        total_tasks: taskvec.len() as i32,
+        completed_tasks: taskvec.iter().filter(|t| t.completed).count() as i32,
+        burndown: burndown(&taskvec, preferences_of(&mut conn, &user).await?.today()),
+    })
+}
 
 #[server]
 pub async fn create_role(name: String) -> Result<Role, ServerFnError> {
     use super::schema::roles;
 
+    let mut validator = Validator::new();
+    let name = validator.text("name", &name, NAME_MAX);
+    validator.finish()?;
+
     let new_role = Role {
         id: Id(Uuid::now_v7()),
         name
@@ -252,11 +2358,15 @@ pub async fn get_roles() -> Result<Vec<Role>, ServerFnError> {
 pub async fn update_role(role: Role) -> Result<(), ServerFnError> {
     use super::schema::roles::dsl::*;
 
+    let mut validator = Validator::new();
+    let new_name = validator.text("name", &role.name, NAME_MAX);
+    validator.finish()?;
+
     let _guard = DB_MUTEX.lock().await;
     let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
 
     diesel::update(roles.find(role.id))
-        .set(name.eq(role.name))
+        .set(name.eq(new_name))
         .returning(Role::as_returning())
         .get_result(&mut conn)
         .await
@@ -280,3 +2390,296 @@ pub async fn delete_role(role_id: Id) -> Result<(), ServerFnError> {
 
     Ok(())
 }
+
+/// Looks for tasks whose role or backlog no longer exists, and clears those references when
+/// `repair` is set. Only run from the command line, as it goes over the tasks of every user.
+#[cfg(feature = "server")]
+pub(super) async fn command_line_integrity_check(repair: bool) -> Result<IntegrityReport, ServerFnError> {
+    let _guard = DB_MUTEX.lock().await;
+    let mut conn = get_db_connection().await.map_err(|e| ServerFnError::new(format!("Database connection error: {}", e)))?;
+
+    integrity::check(&mut conn, repair).await
+}
+
+#[cfg(all(test, feature = "server"))]
+pub(crate) mod tests {
+    use super::*;
+    use super::super::model::tests::{date, id, task};
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Tz;
+    use diesel_async::SimpleAsyncConnection;
+    use std::future::Future;
+    use std::path::Path;
+
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime")
+            .block_on(future)
+    }
+
+    /// A fresh in-memory database with every migration applied.
+    pub(crate) async fn test_connection() -> DbConnection {
+        let mut conn = SyncConnectionWrapper::<SqliteConnection>::establish(":memory:").await.expect("database");
+        let db = Path::new(env!("CARGO_MANIFEST_DIR")).join("db");
+        let mut migrations: Vec<_> = std::fs::read_dir(db.join("migrations"))
+            .expect("migrations")
+            .map(|entry| entry.expect("migration").path())
+            .collect();
+        migrations.sort();
+        for dir in std::iter::once(db.join("initial_schema")).chain(migrations) {
+            let sql = std::fs::read_to_string(dir.join("up.sql")).expect("up.sql");
+            conn.batch_execute(&sql).await.expect("migration");
+        }
+        conn.batch_execute("PRAGMA foreign_keys = ON").await.expect("pragma");
+        conn
+    }
+
+    pub(crate) async fn insert_user(conn: &mut DbConnection, name: &str) -> User {
+        let user = User { id: id(), name: name.to_string() };
+        diesel::insert_into(super::super::schema::users::table).values(&user).execute(conn).await.expect("user");
+        user
+    }
+
+    /// A backlog, with `member` as its owner when given.
+    pub(crate) async fn insert_backlog(conn: &mut DbConnection, member: Option<&User>, archived: bool) -> Id {
+        use super::super::schema::{backlogs, backlog_members};
+
+        let backlog = Backlog {
+            id: id(),
+            name: "Backlog".to_string(),
+            project_id: None,
+            position: 0,
+            archived_at: archived.then(utc_now),
+            deleted_at: None,
+        };
+        diesel::insert_into(backlogs::table).values(&backlog).execute(conn).await.expect("backlog");
+        if let Some(user) = member {
+            let membership = BacklogMember { backlog_id: backlog.id, user_id: user.id, role: MemberRole::Owner };
+            diesel::insert_into(backlog_members::table).values(&membership).execute(conn).await.expect("member");
+        }
+        backlog.id
+    }
+
+    /// Inserts `new_tasks` as created by `owner`.
+    async fn insert_tasks(conn: &mut DbConnection, owner: &User, new_tasks: &[Task]) {
+        for task in new_tasks {
+            let task = Task { owner_id: Some(owner.id), ..task.clone() };
+            diesel::insert_into(super::super::schema::tasks::table).values(&task).execute(conn).await.expect("task");
+        }
+    }
+
+    async fn insert_tag(conn: &mut DbConnection, name: &str, tagged: &[&Task]) -> Id {
+        use super::super::schema::{tags, task_tags};
+
+        let tag = Tag { id: id(), name: name.to_string(), color: TAG_COLORS[0].to_string() };
+        diesel::insert_into(tags::table).values(&tag).execute(conn).await.expect("tag");
+        for task in tagged {
+            let task_tag = TaskTag { task_id: task.id, tag_id: tag.id };
+            diesel::insert_into(task_tags::table).values(&task_tag).execute(conn).await.expect("task tag");
+        }
+        tag.id
+    }
+
+    async fn titles(conn: &mut DbConnection, filter: TaskFilter, user: &User) -> Vec<String> {
+        let mut titles: Vec<String> = query_tasks(conn, filter, user).await.expect("query").into_iter().map(|t| t.title).collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn date_and_backlog_filters() {
+        block_on(async {
+            let mut conn = test_connection().await;
+            let user = insert_user(&mut conn, "ann").await;
+            let member_of = insert_backlog(&mut conn, Some(&user), false).await;
+            let hidden = insert_backlog(&mut conn, None, false).await;
+            let archived = insert_backlog(&mut conn, Some(&user), true).await;
+            let other = insert_user(&mut conn, "bob").await;
+            let day = Some(date(10));
+
+            insert_tasks(&mut conn, &user, &[
+                Task { scheduled_date: day, ..task("inbox") },
+                Task { scheduled_date: Some(date(12)), ..task("inbox later") },
+                task("unscheduled"),
+                Task { scheduled_date: day, deleted_at: Some(utc_now()), ..task("deleted") },
+                Task { scheduled_date: day, backlog_id: Some(member_of), ..task("backlog") },
+                Task { backlog_id: Some(member_of), ..task("backlog unscheduled") },
+                Task { scheduled_date: day, backlog_id: Some(hidden), ..task("hidden") },
+                Task { scheduled_date: day, backlog_id: Some(archived), ..task("archived backlog") },
+            ]).await;
+            insert_tasks(&mut conn, &other, &[
+                Task { scheduled_date: day, ..task("other inbox") },
+                task("other unscheduled"),
+            ]).await;
+
+            assert_eq!(titles(&mut conn, TaskFilter::day(date(10)), &user).await, ["backlog", "inbox"]);
+            assert_eq!(titles(&mut conn, TaskFilter::default(), &user).await, ["unscheduled"]);
+            assert_eq!(
+                titles(&mut conn, TaskFilter { scheduled: DateFilter::Between(Some(date(10)), None), ..Default::default() }, &user).await,
+                ["inbox", "inbox later"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { scheduled: DateFilter::Between(None, Some(date(11))), backlog: BacklogFilter::Any, ..Default::default() }, &user).await,
+                ["backlog", "inbox"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { scheduled: DateFilter::Any, ..TaskFilter::backlog(member_of) }, &user).await,
+                ["backlog", "backlog unscheduled"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { scheduled: DateFilter::Any, ..TaskFilter::backlog(archived) }, &user).await,
+                ["archived backlog"],
+            );
+            assert!(query_tasks(&mut conn, TaskFilter::backlog(hidden), &user).await.is_err());
+            assert_eq!(titles(&mut conn, TaskFilter::day(date(10)), &other).await, ["other inbox"]);
+            assert_eq!(titles(&mut conn, TaskFilter::default(), &other).await, ["other unscheduled"]);
+        });
+    }
+
+    #[test]
+    fn completed_flag_and_archive_filters() {
+        block_on(async {
+            let mut conn = test_connection().await;
+            let user = insert_user(&mut conn, "ann").await;
+            let done_on = |day| Some(date(day).and_hms_opt(17, 0, 0).unwrap());
+
+            insert_tasks(&mut conn, &user, &[
+                Task { important: true, ..task("important") },
+                Task { important: true, urgent: true, ..task("important urgent") },
+                Task { urgent: true, completed: true, completed_at: done_on(3), ..task("urgent done") },
+                Task { important: true, completed: true, completed_at: done_on(8), ..task("important done") },
+                Task { completed: true, completed_at: done_on(2), archived_at: done_on(4), ..task("archived") },
+            ]).await;
+
+            let any_date = TaskFilter { scheduled: DateFilter::Any, ..Default::default() };
+            assert_eq!(
+                titles(&mut conn, TaskFilter { completed: Some(false), ..any_date.clone() }, &user).await,
+                ["important", "important urgent"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { completed: Some(true), important: Some(true), ..any_date.clone() }, &user).await,
+                ["important done"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { important: Some(false), urgent: Some(true), ..any_date.clone() }, &user).await,
+                ["urgent done"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { completed_since: Some(date(3)), ..any_date.clone() }, &user).await,
+                ["important done", "urgent done"],
+            );
+            assert_eq!(
+                titles(&mut conn, TaskFilter { completed_since: Some(date(4)), urgent: Some(true), ..any_date.clone() }, &user).await,
+                Vec::<String>::new(),
+            );
+            assert_eq!(titles(&mut conn, TaskFilter { archived: true, ..any_date.clone() }, &user).await, ["archived"]);
+            assert_eq!(
+                titles(&mut conn, TaskFilter { archived: true, completed_since: Some(date(3)), ..any_date }, &user).await,
+                Vec::<String>::new(),
+            );
+        });
+    }
+
+    #[test]
+    fn tag_text_and_assignee_filters() {
+        block_on(async {
+            let mut conn = test_connection().await;
+            let user = insert_user(&mut conn, "ann").await;
+            let other = insert_user(&mut conn, "bob").await;
+            // Shared, so both see the tasks assigned to them
+            let shared = insert_backlog(&mut conn, Some(&user), false).await;
+            let membership = BacklogMember { backlog_id: shared, user_id: other.id, role: MemberRole::Editor };
+            diesel::insert_into(super::super::schema::backlog_members::table).values(&membership).execute(&mut conn).await.expect("member");
+
+            let rent = Task { assignee_id: Some(user.id), ..task("Pay 100% of rent") };
+            let groceries = Task { content: Some("Milk_and eggs".to_string()), ..task("Groceries") };
+            let taxes = Task { assignee_id: Some(other.id), backlog_id: Some(shared), ..task("Pay 1000 in taxes") };
+            insert_tasks(&mut conn, &user, &[rent.clone(), groceries.clone(), taxes.clone()]).await;
+            let money = insert_tag(&mut conn, "money", &[&rent, &taxes]).await;
+            let urgent = insert_tag(&mut conn, "urgent", &[&taxes, &groceries]).await;
+
+            let any_date = TaskFilter { scheduled: DateFilter::Any, backlog: BacklogFilter::Any, ..Default::default() };
+            assert_eq!(
+                titles(&mut conn, TaskFilter { tags: vec![money], ..any_date.clone() }, &user).await,
+                ["Pay 100% of rent", "Pay 1000 in taxes"],
+            );
+            assert_eq!(titles(&mut conn, TaskFilter { tags: vec![money, urgent], ..any_date.clone() }, &user).await, ["Pay 1000 in taxes"]);
+            assert_eq!(
+                titles(&mut conn, TaskFilter { text: Some("pay".to_string()), ..any_date.clone() }, &user).await,
+                ["Pay 100% of rent", "Pay 1000 in taxes"],
+            );
+            assert_eq!(titles(&mut conn, TaskFilter { text: Some("0%".to_string()), ..any_date.clone() }, &user).await, ["Pay 100% of rent"]);
+            assert_eq!(titles(&mut conn, TaskFilter { text: Some("K_A".to_string()), ..any_date.clone() }, &user).await, ["Groceries"]);
+            assert_eq!(titles(&mut conn, TaskFilter { assigned_to_me: true, ..any_date.clone() }, &user).await, ["Pay 100% of rent"]);
+            assert_eq!(
+                titles(&mut conn, TaskFilter { assigned_to_me: true, tags: vec![urgent], ..any_date }, &other).await,
+                ["Pay 1000 in taxes"],
+            );
+        });
+    }
+
+    #[test]
+    fn sort_limit_and_offset() {
+        async fn ordered(conn: &mut DbConnection, user: &User, sort: TaskSort, limit: Option<i64>, offset: Option<i64>) -> Vec<String> {
+            let filter = TaskFilter { scheduled: DateFilter::Any, sort, limit, offset, ..Default::default() };
+            query_tasks(conn, filter, user).await.expect("query").into_iter().map(|t| t.title).collect()
+        }
+
+        block_on(async {
+            let mut conn = test_connection().await;
+            let user = insert_user(&mut conn, "ann").await;
+
+            // Created in this order, which `id()` keeps
+            insert_tasks(&mut conn, &user, &[
+                Task { scheduled_date: Some(date(20)), ..task("c") },
+                Task { important: true, ..task("a") },
+                Task { scheduled_date: Some(date(10)), urgent: true, ..task("d") },
+                Task { updated_at: date(9).and_hms_opt(9, 0, 0), ..task("b") },
+            ]).await;
+
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Created, None, None).await, ["c", "a", "d", "b"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Title, None, None).await, ["a", "b", "c", "d"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Scheduled, None, None).await, ["a", "b", "d", "c"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Updated, None, None).await, ["b", "d", "a", "c"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Priority, None, None).await, ["a", "d", "c", "b"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Title, Some(2), None).await, ["a", "b"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Title, Some(2), Some(1)).await, ["b", "c"]);
+            assert_eq!(ordered(&mut conn, &user, TaskSort::Title, Some(10), Some(3)).await, ["d"]);
+        });
+    }
+
+    #[test]
+    fn rollover_waits_for_each_users_midnight() {
+        block_on(async {
+            let mut conn = test_connection().await;
+            let ann = insert_user(&mut conn, "ann").await;
+            let bob = insert_user(&mut conn, "bob").await;
+            let shared = insert_backlog(&mut conn, Some(&ann), false).await;
+            let membership = BacklogMember { backlog_id: shared, user_id: bob.id, role: MemberRole::Editor };
+            diesel::insert_into(super::super::schema::backlog_members::table).values(&membership).execute(&mut conn).await.expect("member");
+
+            // Just after midnight for ann in Tokyo, still the morning before for bob in New York
+            let now = Utc.with_ymd_and_hms(2025, 3, 10, 15, 30, 0).unwrap();
+            let tokyo_today = now.with_timezone(&Tz::Asia__Tokyo).date_naive();
+            let new_york_today = now.with_timezone(&Tz::America__New_York).date_naive();
+            assert_eq!((tokyo_today, new_york_today), (date(11), date(10)));
+
+            let day = Some(date(10));
+            insert_tasks(&mut conn, &ann, &[
+                Task { scheduled_date: day, ..task("ann inbox") },
+                Task { scheduled_date: day, backlog_id: Some(shared), ..task("ann backlog") },
+                Task { scheduled_date: day, backlog_id: Some(shared), assignee_id: Some(bob.id), ..task("bob backlog") },
+            ]).await;
+            insert_tasks(&mut conn, &bob, &[Task { scheduled_date: day, ..task("bob inbox") }]).await;
+
+            let report = roll_over(&mut conn, RolloverPolicy::MoveToToday, tokyo_today, &ann).await.expect("rollover");
+            assert_eq!(report.rolled_over, 2);
+            assert_eq!(titles(&mut conn, TaskFilter::day(date(11)), &ann).await, ["ann backlog", "ann inbox"]);
+            assert_eq!(titles(&mut conn, TaskFilter::day(date(10)), &bob).await, ["bob backlog", "bob inbox"]);
+
+            let report = roll_over(&mut conn, RolloverPolicy::MoveToToday, new_york_today, &bob).await.expect("rollover");
+            assert_eq!(report.rolled_over, 0);
+        });
+    }
+}
@@ -0,0 +1,344 @@
+//! Checks on everything clients send to the server functions.
+//!
+//! A `Validator` collects the problems with each field instead of stopping at the first one, and
+//! turns them into a single error. The field errors travel as JSON inside the error message, so
+//! the client can get them back with `field_errors` and show each next to its input.
+
+use chrono::{Datelike, NaiveDate};
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use super::model::*;
+use super::preferences::{Preferences, WEEK_STARTS};
+
+pub const TITLE_MAX: usize = 200;
+pub const CONTENT_MAX: usize = 10_000;
+/// Names of backlogs, roles, tags, projects, columns, milestones, views and users.
+pub const NAME_MAX: usize = 80;
+pub const QUERY_MAX: usize = 1_000;
+/// Dates outside these years are taken to be typos.
+pub const YEARS: RangeInclusive<i32> = 2000..=2100;
+/// Estimates and capacities are in minutes; nothing takes longer than a week.
+pub const MINUTES_MAX: i32 = 7 * 24 * 60;
+pub const DAY_MINUTES: i32 = 24 * 60;
+/// Longest range of days fetched or exported at once.
+pub const RANGE_DAYS_MAX: i64 = 366;
+pub const LIMIT_MAX: i64 = 1_000;
+
+const ERROR_PREFIX: &str = "Invalid input: ";
+const CONFLICT_PREFIX: &str = "Conflict: ";
+
+/// A problem with one field of the input.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for FieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// The field errors of a failed server call; empty when it failed for another reason.
+pub fn field_errors(error: &ServerFnError) -> Vec<FieldError> {
+    let message = error.to_string();
+    message
+        .find(ERROR_PREFIX)
+        .and_then(|start| serde_json::from_str(&message[start + ERROR_PREFIX.len()..]).ok())
+        .unwrap_or_default()
+}
+
+/// A short description of why a server call failed, for showing next to an input.
+pub fn error_message(error: &ServerFnError) -> String {
+    let errors = field_errors(error);
+    if errors.is_empty() {
+        return error.to_string();
+    }
+    errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// The error for a write based on a copy that went stale in the meantime.
+pub fn conflict_error(message: &str) -> ServerFnError {
+    ServerFnError::new(format!("{}{}", CONFLICT_PREFIX, message))
+}
+
+/// Whether a server call failed because someone else changed the data first.
+pub fn is_conflict(error: &ServerFnError) -> bool {
+    error.to_string().contains(CONFLICT_PREFIX)
+}
+
+/// Collects field errors while the input is checked.
+#[derive(Debug, Default)]
+pub struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn error(&mut self, field: &str, message: impl Into<String>) {
+        self.errors.push(FieldError { field: field.to_string(), message: message.into() });
+    }
+
+    /// Trimmed text that has to be there and may be at most `max` characters long.
+    pub fn text(&mut self, field: &str, value: &str, max: usize) -> String {
+        let value = value.trim();
+        if value.is_empty() {
+            self.error(field, "must not be empty");
+        } else if value.chars().count() > max {
+            self.error(field, format!("must be at most {} characters", max));
+        }
+        value.to_string()
+    }
+
+    /// Trimmed text that may be left out; blank counts as left out.
+    pub fn optional_text(&mut self, field: &str, value: Option<&str>, max: usize) -> Option<String> {
+        let value = value.map(str::trim).filter(|v| !v.is_empty())?;
+        if value.chars().count() > max {
+            self.error(field, format!("must be at most {} characters", max));
+        }
+        Some(value.to_string())
+    }
+
+    pub fn date(&mut self, field: &str, value: Option<NaiveDate>) {
+        if let Some(date) = value.filter(|d| !YEARS.contains(&d.year())) {
+            self.error(field, format!("{} is not between {} and {}", date, YEARS.start(), YEARS.end()));
+        }
+    }
+
+    /// A start and end date, both sane and in order, at most `RANGE_DAYS_MAX` apart.
+    pub fn date_range(&mut self, field: &str, start: NaiveDate, end: NaiveDate) {
+        self.date(field, Some(start));
+        self.date(field, Some(end));
+        if end < start {
+            self.error(field, "must not end before it starts");
+        } else if (end - start).num_days() >= RANGE_DAYS_MAX {
+            self.error(field, format!("must cover at most {} days", RANGE_DAYS_MAX));
+        }
+    }
+
+    /// A number of minutes or other count within `range`, when given.
+    pub fn number(&mut self, field: &str, value: Option<i32>, range: RangeInclusive<i32>) {
+        if let Some(value) = value.filter(|v| !range.contains(v)) {
+            self.error(field, format!("{} is not between {} and {}", value, range.start(), range.end()));
+        }
+    }
+
+    /// A tag color such as `#4caf50`.
+    pub fn color(&mut self, field: &str, value: &str) {
+        let valid = value.len() == 7
+            && value.starts_with('#')
+            && value[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            self.error(field, "must be a color like #4caf50");
+        }
+    }
+
+    /// Checks the fields of a task a client sent, returning it trimmed.
+    pub fn task(&mut self, task: Task) -> Task {
+        let title = self.text("title", &task.title, TITLE_MAX);
+        let content = self.optional_text("content", task.content.as_deref(), CONTENT_MAX);
+        self.date("scheduled_date", task.scheduled_date);
+        self.number("estimate_minutes", task.estimate_minutes, 1..=MINUTES_MAX);
+        if task.start_time.is_some() != task.end_time.is_some() || (task.start_time.is_some() && task.time_block().is_none()) {
+            self.error("end_time", "a time block needs both a start and a later end time");
+        }
+        Task { title, content, ..task }
+    }
+
+    pub fn filter(&mut self, filter: &TaskFilter) {
+        if let DateFilter::On(day) = filter.scheduled {
+            self.date("scheduled", Some(day));
+        }
+        if let DateFilter::Between(from, to) = filter.scheduled {
+            self.date("scheduled", from);
+            self.date("scheduled", to);
+        }
+        self.date("updated_since", filter.updated_since);
+        self.date("completed_since", filter.completed_since);
+        self.optional_text("text", filter.text.as_deref(), QUERY_MAX);
+        if filter.limit.is_some_and(|l| !(1..=LIMIT_MAX).contains(&l)) {
+            self.error("limit", format!("must be between 1 and {}", LIMIT_MAX));
+        }
+        if filter.offset.is_some_and(|o| o < 0) {
+            self.error("offset", "must not be negative");
+        }
+    }
+
+    /// Checks a saved view, returning it with its name and query trimmed.
+    pub fn saved_view(&mut self, view: SavedView) -> SavedView {
+        let name = self.text("name", &view.name, NAME_MAX);
+        let query = self.optional_text("query", view.query.as_deref(), QUERY_MAX);
+        self.filter(&view.filter);
+        self.number("upcoming_days", view.upcoming_days, 1..=RANGE_DAYS_MAX as i32);
+        SavedView { name, query, ..view }
+    }
+
+    /// Checks preferences a client sent. Their time zone is checked when they are deserialized.
+    pub fn preferences(&mut self, preferences: &Preferences) {
+        if !WEEK_STARTS.contains(&preferences.week_start) {
+            self.error("week_start", format!("{} is not a day weeks can start on", preferences.week_start));
+        }
+    }
+
+    /// Fails with every error found so far, if there are any.
+    pub fn finish(self) -> Result<(), ServerFnError> {
+        if self.errors.is_empty() {
+            return Ok(());
+        }
+        let errors = serde_json::to_string(&self.errors)
+            .map_err(|e| ServerFnError::new(format!("Validation error serialize error: {}", e)))?;
+        Err(ServerFnError::new(format!("{}{}", ERROR_PREFIX, errors)))
+    }
+}
+
+/// Checks that the role, backlog, assignee and project a task points at exist.
+#[cfg(feature = "server")]
+pub async fn check_task_references(conn: &mut super::server::DbConnection, validator: &mut Validator, task: &Task) -> Result<(), ServerFnError> {
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+    use super::schema::{backlogs, projects, roles, users};
+
+    let fetch_error = |e: diesel::result::Error| ServerFnError::new(format!("Database fetch error: {}", e));
+
+    if let Some(role) = task.role_id {
+        let found: i64 = roles::table.find(role).count().get_result(conn).await.map_err(fetch_error)?;
+        if found == 0 {
+            validator.error("role_id", "no such role");
+        }
+    }
+    if let Some(backlog) = task.backlog_id {
+        let found: i64 = backlogs::table
+            .find(backlog)
+            .filter(backlogs::deleted_at.is_null())
+            .count()
+            .get_result(conn)
+            .await
+            .map_err(fetch_error)?;
+        if found == 0 {
+            validator.error("backlog_id", "no such backlog");
+        }
+    }
+    if let Some(assignee) = task.assignee_id {
+        let found: i64 = users::table.find(assignee).count().get_result(conn).await.map_err(fetch_error)?;
+        if found == 0 {
+            validator.error("assignee_id", "no such user");
+        }
+    }
+    if let Some(project) = task.project_id {
+        let found: i64 = projects::table.find(project).count().get_result(conn).await.map_err(fetch_error)?;
+        if found == 0 {
+            validator.error("project_id", "no such project");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::model::tests::{date, task};
+    use chrono::{NaiveTime, Weekday};
+
+    /// The fields with errors after running `check` on a fresh validator.
+    fn failing(check: impl FnOnce(&mut Validator)) -> Vec<String> {
+        let mut validator = Validator::new();
+        check(&mut validator);
+        match validator.finish() {
+            Ok(()) => vec![],
+            Err(e) => field_errors(&e).into_iter().map(|e| e.field).collect(),
+        }
+    }
+
+    #[test]
+    fn text_is_trimmed_and_limited() {
+        let mut validator = Validator::new();
+        assert_eq!(validator.text("name", "  Work ", NAME_MAX), "Work");
+        assert_eq!(validator.optional_text("content", Some("   "), CONTENT_MAX), None);
+        assert!(validator.finish().is_ok());
+
+        assert_eq!(failing(|v| { v.text("name", " ", NAME_MAX); }), ["name"]);
+        assert_eq!(failing(|v| { v.text("name", &"x".repeat(NAME_MAX + 1), NAME_MAX); }), ["name"]);
+        assert_eq!(failing(|v| { v.text("name", &"é".repeat(NAME_MAX), NAME_MAX); }), Vec::<String>::new());
+    }
+
+    #[test]
+    fn dates_numbers_and_colors() {
+        let far_off = NaiveDate::from_ymd_opt(1999, 12, 31);
+        assert_eq!(failing(|v| v.date("day", far_off)), ["day"]);
+        assert_eq!(failing(|v| v.date_range("end", date(10), date(9))), ["end"]);
+        assert_eq!(failing(|v| v.date_range("end", date(1), date(1) + chrono::Duration::days(RANGE_DAYS_MAX))), ["end"]);
+        assert_eq!(failing(|v| v.date_range("end", date(1), date(31))), Vec::<String>::new());
+        assert_eq!(failing(|v| v.number("minutes", Some(0), 1..=MINUTES_MAX)), ["minutes"]);
+        assert_eq!(failing(|v| v.number("minutes", None, 1..=MINUTES_MAX)), Vec::<String>::new());
+        assert_eq!(failing(|v| v.color("color", "#4caf5g")), ["color"]);
+        assert_eq!(failing(|v| v.color("color", "#4CAF50")), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tasks_need_a_title_and_a_whole_time_block() {
+        let nine = NaiveTime::from_hms_opt(9, 0, 0);
+        let ten = NaiveTime::from_hms_opt(10, 0, 0);
+        assert_eq!(failing(|v| { v.task(Task { start_time: nine, end_time: ten, ..task("Call") }); }), Vec::<String>::new());
+        assert_eq!(failing(|v| { v.task(Task { start_time: nine, ..task("Call") }); }), ["end_time"]);
+        assert_eq!(failing(|v| { v.task(Task { start_time: ten, end_time: nine, ..task("Call") }); }), ["end_time"]);
+        assert_eq!(failing(|v| { v.task(Task { estimate_minutes: Some(0), ..task(" ") }); }), ["title", "estimate_minutes"]);
+    }
+
+    #[test]
+    fn filters_check_every_date_and_paging() {
+        let far_off = NaiveDate::from_ymd_opt(2200, 1, 1);
+        let filter = TaskFilter {
+            scheduled: DateFilter::Between(far_off, None),
+            updated_since: far_off,
+            completed_since: far_off,
+            limit: Some(0),
+            offset: Some(-1),
+            ..Default::default()
+        };
+        assert_eq!(failing(|v| v.filter(&filter)), ["scheduled", "updated_since", "completed_since", "limit", "offset"]);
+        assert_eq!(failing(|v| v.filter(&TaskFilter::day(date(10)))), Vec::<String>::new());
+    }
+
+    #[test]
+    fn weeks_start_on_offered_days() {
+        assert_eq!(failing(|v| v.preferences(&Preferences::default())), Vec::<String>::new());
+        assert_eq!(failing(|v| v.preferences(&Preferences { week_start: Weekday::Wed, ..Default::default() })), ["week_start"]);
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn task_references_must_exist() {
+        use diesel::prelude::*;
+        use diesel_async::RunQueryDsl;
+        use super::super::model::tests::id;
+        use super::super::server::tests::{block_on, insert_backlog, insert_user, test_connection};
+
+        block_on(async {
+            let mut conn = test_connection().await;
+            let user = insert_user(&mut conn, "ann").await;
+            let backlog = insert_backlog(&mut conn, Some(&user), false).await;
+            let deleted = insert_backlog(&mut conn, Some(&user), false).await;
+            diesel::update(super::super::schema::backlogs::table.find(deleted))
+                .set(super::super::schema::backlogs::deleted_at.eq(super::super::preferences::utc_now()))
+                .execute(&mut conn)
+                .await
+                .expect("delete backlog");
+
+            let mut validator = Validator::new();
+            let existing = Task { backlog_id: Some(backlog), assignee_id: Some(user.id), ..task("existing") };
+            check_task_references(&mut conn, &mut validator, &existing).await.expect("check");
+            assert!(validator.finish().is_ok());
+
+            let mut validator = Validator::new();
+            let dangling = Task { role_id: Some(id()), backlog_id: Some(deleted), assignee_id: Some(id()), project_id: Some(id()), ..task("dangling") };
+            check_task_references(&mut conn, &mut validator, &dangling).await.expect("check");
+            let fields: Vec<String> = field_errors(&validator.finish().unwrap_err()).into_iter().map(|e| e.field).collect();
+            assert_eq!(fields, ["role_id", "backlog_id", "assignee_id", "project_id"]);
+        });
+    }
+}
@@ -1,13 +1,15 @@
 use dioxus::prelude::*;
 use super::item::ItemList;
+use super::project::ProjectSelect;
 use crate::backend::server;
-use crate::backend::model::{Backlog, TaskFilter};
+use crate::backend::model::{Backlog, BacklogColumn, BacklogRemoval, DEFAULT_COLUMNS, Id, Member, MemberRole, TaskFilter};
+use super::undo::TASKS_VERSION;
 use dioxus_primitives::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
 
 #[component]
 pub fn BacklogApp() -> Element {
     let mut dialog_open = use_signal(|| false);
-    let mut backlogs = use_signal(|| vec![]);
+    let mut backlogs: Signal<Vec<Backlog>> = use_signal(|| vec![]);
 
     use_hook(|| {
         spawn(async move {
@@ -31,7 +33,7 @@ pub fn BacklogApp() -> Element {
                 onclick: move |_| dialog_open.set(true),
                 "Manage Backlogs"
             }
-            BacklogList { backlogs: backlogs.read().clone() },
+            BacklogList { backlogs: backlogs.read().iter().filter(|b| b.archived_at.is_none()).cloned().collect::<Vec<_>>() },
             DialogRoot {
                 class: "dialog-backdrop",
                 open: dialog_open(),
@@ -45,7 +47,7 @@ pub fn BacklogApp() -> Element {
                         onclick: move |_| dialog_open.set(false),
                         "×"
                     }
-                    BacklogManager { }
+                    BacklogManager { backlogs: backlogs }
                 }
             }
         }
@@ -66,22 +68,393 @@ pub fn BacklogList(backlogs: Vec<Backlog>) -> Element {
 
 #[component]
 pub fn BacklogBoard(backlog: Backlog) -> Element {
+    let mut members = use_signal(|| vec![]);
+    let mut assigned_to_me = use_signal(|| false);
+    let mut members_open = use_signal(|| false);
+    let mut project_id = use_signal(|| backlog.project_id);
+    let mut columns: Signal<Vec<BacklogColumn>> = use_signal(|| vec![]);
+    let mut columns_open = use_signal(|| false);
+    let backlog_id = backlog.id;
+
+    use_hook(|| {
+        spawn(async move {
+            match server::get_backlog_members(backlog_id).await {
+                Ok(fetched) => members.set(fetched),
+                Err(e) => eprintln!("Failed to fetch backlog members: {}", e),
+            }
+            match server::get_backlog_columns(backlog_id).await {
+                Ok(fetched) => columns.set(fetched),
+                Err(e) => eprintln!("Failed to fetch backlog columns: {}", e),
+            }
+        });
+    });
+
     rsx! { 
         div {
             class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
             "{backlog.name}",
-            ItemList { filter: TaskFilter {scheduled_date: None, backlog_id: Some(backlog.id)} } 
+            if members.read().len() > 1 {
+                span { class: "ml-2 text-sm", "(shared with {members.read().len() - 1})" }
+            }
+            div {
+                class: "flex justify-center gap-2 my-2",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: assigned_to_me(),
+                        onchange: move |evt| assigned_to_me.set(evt.checked()),
+                    }
+                    " Assigned to me"
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| members_open.set(true),
+                    "Members"
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| columns_open.set(true),
+                    "Columns"
+                }
+                ProjectSelect {
+                    selected: project_id(),
+                    on_change: {
+                        let backlog = backlog.clone();
+                        move |new_project: Option<Id>| {
+                            let backlog = Backlog { project_id: new_project, ..backlog.clone() };
+                            spawn(async move {
+                                match server::update_backlog(backlog).await {
+                                    Ok(_) => project_id.set(new_project),
+                                    Err(e) => eprintln!("Failed to move backlog to project: {}", e),
+                                }
+                            });
+                        }
+                    },
+                }
+            }
+            // Keyed on the toggle so the list refetches when it changes
+            ItemList {
+                key: "{backlog.id.0}-{assigned_to_me}",
+                filter: TaskFilter {assigned_to_me: assigned_to_me(), ..TaskFilter::backlog(backlog.id)},
+                members: members.read().clone(),
+                columns: columns.read().clone(),
+            }
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: members_open(),
+                on_open_change: move |v| members_open.set(v),
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if members_open() { "0" } else { "-1" },
+                        onclick: move |_| members_open.set(false),
+                        "×"
+                    }
+                    BacklogMembers { backlog: backlog.clone(), members: members }
+                }
+            }
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: columns_open(),
+                on_open_change: move |v| columns_open.set(v),
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if columns_open() { "0" } else { "-1" },
+                        onclick: move |_| columns_open.set(false),
+                        "×"
+                    }
+                    BacklogColumns { backlog: backlog.clone(), columns: columns }
+                }
+            }
         }
     }
 }
 
 #[component]
-pub fn BacklogManager() -> Element {
+fn BacklogMembers(backlog: Backlog, members: Signal<Vec<Member>>) -> Element {
+    let mut new_member = use_signal(|| String::new());
+    let mut new_role = use_signal(|| MemberRole::Editor);
+    let backlog_id = backlog.id;
+
+    let add_member_fn = move || {
+        let name = new_member.read().clone();
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            match server::set_backlog_member(backlog_id, name, new_role()).await {
+                Ok(_) => {
+                    new_member.set(String::new());
+                    // Refetch, adding the first member may also have made us owner
+                    match server::get_backlog_members(backlog_id).await {
+                        Ok(fetched) => members.set(fetched),
+                        Err(e) => eprintln!("Failed to fetch backlog members: {}", e),
+                    }
+                },
+                Err(e) => eprintln!("Failed to add backlog member: {}", e),
+            }
+        });
+    };
+
+    let remove_member_fn = move |user_id: Id| {
+        spawn(async move {
+            match server::remove_backlog_member(backlog_id, user_id).await {
+                Ok(_) => members.write().retain(|m| m.user.id != user_id),
+                Err(e) => eprintln!("Failed to remove backlog member: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Members of {backlog.name}"
+        }
+        DialogDescription {
+            for member in members.read().clone() {
+                div {
+                    key: "{member.user.id.0}",
+                    class: "flex items-center gap-2",
+                    span { class: "flex-1", "{member.user.name}" }
+                    span { "{member.role}" }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| remove_member_fn(member.user.id),
+                        "Remove"
+                    }
+                }
+            }
+            div {
+                class: "flex mt-2",
+                input {
+                    class: "inbox-input flex-1",
+                    r#type: "text",
+                    placeholder: "User name",
+                    value: "{new_member}",
+                    oninput: move |evt| new_member.set(evt.value()),
+                    onkeydown: move |evt| match evt.key() {
+                        Key::Enter => add_member_fn(),
+                        Key::Escape => new_member.set(String::new()),
+                        _ => {}
+                    }
+                }
+                select {
+                    class: "inbox-assignee",
+                    aria_label: "Role",
+                    onchange: move |evt| {
+                        if let Ok(role) = evt.value().parse() {
+                            new_role.set(role);
+                        }
+                    },
+                    for role in MemberRole::ALL {
+                        option {
+                            value: "{role}",
+                            selected: new_role() == role,
+                            "{role}"
+                        }
+                    }
+                }
+                button {
+                    class: "inbox-button",
+                    onclick: move |_| add_member_fn(), "Add"
+                }
+            }
+        }
+    }
+}
+
+/// Sets up the status columns of a backlog's board and their WIP limits.
+#[component]
+fn BacklogColumns(backlog: Backlog, columns: Signal<Vec<BacklogColumn>>) -> Element {
+    let mut new_column = use_signal(|| String::new());
+    let backlog_id = backlog.id;
+
+    let fetch_fn = move || {
+        spawn(async move {
+            match server::get_backlog_columns(backlog_id).await {
+                Ok(fetched) => columns.set(fetched),
+                Err(e) => eprintln!("Failed to fetch backlog columns: {}", e),
+            }
+        });
+    };
+
+    let add_columns_fn = move |names: Vec<String>| {
+        spawn(async move {
+            for name in names {
+                if let Err(e) = server::create_backlog_column(backlog_id, name, None).await {
+                    eprintln!("Failed to create column: {}", e);
+                }
+            }
+            new_column.set(String::new());
+            fetch_fn();
+        });
+    };
+
+    let update_column_fn = move |column: BacklogColumn| {
+        spawn(async move {
+            match server::update_backlog_column(column).await {
+                Ok(updated) => {
+                    if let Some(c) = columns.write().iter_mut().find(|c| c.id == updated.id) {
+                        *c = updated;
+                    }
+                },
+                Err(e) => eprintln!("Failed to update column: {}", e),
+            }
+        });
+    };
+
+    let delete_column_fn = move |column_id: Id| {
+        spawn(async move {
+            match server::delete_backlog_column(column_id).await {
+                Ok(_) => columns.write().retain(|c| c.id != column_id),
+                Err(e) => eprintln!("Failed to delete column: {}", e),
+            }
+        });
+    };
+
+    // Swaps a column with its neighbour to the left (-1) or right (+1)
+    let move_column_fn = move |index: usize, offset: isize| {
+        let mut ids: Vec<Id> = columns.read().iter().map(|c| c.id).collect();
+        let Some(other) = index.checked_add_signed(offset).filter(|i| *i < ids.len()) else {
+            return;
+        };
+        ids.swap(index, other);
+        spawn(async move {
+            match server::reorder_backlog_columns(backlog_id, ids).await {
+                Ok(_) => fetch_fn(),
+                Err(e) => eprintln!("Failed to reorder columns: {}", e),
+            }
+        });
+    };
+
+    let add_column_fn = move || {
+        let name = new_column.read().trim().to_string();
+        if !name.is_empty() {
+            add_columns_fn(vec![name]);
+        }
+    };
+
+    let default_columns = DEFAULT_COLUMNS.join(", ");
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Columns of {backlog.name}"
+        }
+        DialogDescription {
+            if columns.read().is_empty() {
+                p { "Without columns the backlog is a single list." }
+                button {
+                    class: "button",
+                    "data-style": "outline",
+                    onclick: move |_| add_columns_fn(DEFAULT_COLUMNS.iter().map(|name| name.to_string()).collect()),
+                    "Add {default_columns}"
+                }
+            }
+            for (index, column) in columns.read().clone().into_iter().enumerate() {
+                div {
+                    key: "{column.id.0}",
+                    class: "flex items-center gap-2",
+                    input {
+                        class: "inbox-input flex-1",
+                        r#type: "text",
+                        value: "{column.name}",
+                        onchange: {
+                            let column = column.clone();
+                            move |evt: FormEvent| {
+                                if !evt.value().trim().is_empty() {
+                                    update_column_fn(BacklogColumn { name: evt.value(), ..column.clone() });
+                                }
+                            }
+                        },
+                    }
+                    input {
+                        class: "inbox-input w-20",
+                        r#type: "number",
+                        min: "0",
+                        placeholder: "WIP limit",
+                        aria_label: "WIP limit",
+                        value: "{column.wip_limit.map(|l| l.to_string()).unwrap_or_default()}",
+                        onchange: {
+                            let column = column.clone();
+                            move |evt: FormEvent| {
+                                let wip_limit = evt.value().trim().parse::<i32>().ok().filter(|l| *l > 0);
+                                update_column_fn(BacklogColumn { wip_limit, ..column.clone() });
+                            }
+                        },
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move left",
+                        onclick: move |_| move_column_fn(index, -1),
+                        "←"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move right",
+                        onclick: move |_| move_column_fn(index, 1),
+                        "→"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| delete_column_fn(column.id),
+                        "Delete"
+                    }
+                }
+            }
+            div {
+                class: "flex mt-2",
+                input {
+                    class: "inbox-input flex-1",
+                    r#type: "text",
+                    placeholder: "Column name",
+                    value: "{new_column}",
+                    oninput: move |evt| new_column.set(evt.value()),
+                    onkeydown: move |evt| match evt.key() {
+                        Key::Enter => add_column_fn(),
+                        Key::Escape => new_column.set(String::new()),
+                        _ => {}
+                    }
+                }
+                button {
+                    class: "inbox-button",
+                    onclick: move |_| add_column_fn(), "Add"
+                }
+            }
+        }
+    }
+}
+
+/// Creates, renames, reorders, archives and deletes backlogs.
+#[component]
+pub fn BacklogManager(backlogs: Signal<Vec<Backlog>>) -> Element {
     let mut new_backlog = use_signal(|| String::new());
+    // Backlog whose delete options are shown
+    let mut removing: Signal<Option<Id>> = use_signal(|| None);
+
+    let fetch_fn = move || {
+        spawn(async move {
+            match server::get_backlogs().await {
+                Ok(fetched) => backlogs.set(fetched),
+                Err(e) => eprintln!("Failed to fetch backlogs: {}", e),
+            }
+        });
+    };
 
     let create_backlog_fn = {
         move || {
-            let name = new_backlog.read().clone();
+            let name = new_backlog.read().trim().to_string();
             if name.is_empty() {
                 return;
             }
@@ -89,7 +462,7 @@ pub fn BacklogManager() -> Element {
                 async move {
                     match server::create_backlog(name).await {
                         Ok(backlog) => {
-                            //backlogs.push(task);
+                            backlogs.write().push(backlog);
                             new_backlog.set(String::new());
                         },
                         Err(e) => eprintln!("Failed to create backlog: {}", e),
@@ -99,18 +472,58 @@ pub fn BacklogManager() -> Element {
         }
     };
 
-    // let delete_backlog_fn = {
-    //     move |id: Id| {
-    //         spawn({
-    //             async move {
-    //                 match server::delete_backlog(id).await {
-    //                     Ok(_) => backlogs.retain(|b| b.id != id),
-    //                     Err(e) => eprintln!("Failed to delete backlog: {}", e),
-    //                 }
-    //             }
-    //         });
-    //     }
-    // };
+    let rename_backlog_fn = move |backlog: Backlog| {
+        spawn(async move {
+            match server::update_backlog(backlog.clone()).await {
+                Ok(_) => {
+                    if let Some(b) = backlogs.write().iter_mut().find(|b| b.id == backlog.id) {
+                        *b = backlog;
+                    }
+                },
+                Err(e) => eprintln!("Failed to rename backlog: {}", e),
+            }
+        });
+    };
+
+    let remove_backlog_fn = move |backlog_id: Id, removal: BacklogRemoval| {
+        spawn(async move {
+            match server::delete_backlog(backlog_id, removal).await {
+                Ok(_) => {
+                    removing.set(None);
+                    fetch_fn();
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to delete backlog: {}", e),
+            }
+        });
+    };
+
+    let restore_backlog_fn = move |backlog_id: Id| {
+        spawn(async move {
+            match server::restore_backlog(backlog_id).await {
+                Ok(_) => {
+                    fetch_fn();
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to restore backlog: {}", e),
+            }
+        });
+    };
+
+    // Swaps a backlog with its neighbour above (-1) or below (+1)
+    let move_backlog_fn = move |index: usize, offset: isize| {
+        let mut ids: Vec<Id> = backlogs.read().iter().map(|b| b.id).collect();
+        let Some(other) = index.checked_add_signed(offset).filter(|i| *i < ids.len()) else {
+            return;
+        };
+        ids.swap(index, other);
+        spawn(async move {
+            match server::reorder_backlogs(ids).await {
+                Ok(_) => fetch_fn(),
+                Err(e) => eprintln!("Failed to reorder backlogs: {}", e),
+            }
+        });
+    };
 
     rsx! {
         DialogTitle {
@@ -118,26 +531,158 @@ pub fn BacklogManager() -> Element {
             "Backlog Management"
         }
         DialogDescription {
-            input {
-                class: "inbox-input flex-1",
-                r#type: "text",
-                placeholder: "Backlog name",
-                value: "{new_backlog}",
-                oninput: move |evt| new_backlog.set(evt.value()),
-                onkeydown: move |evt| match evt.key() {
-                    Key::Enter => create_backlog_fn(),
-                    Key::Escape => new_backlog.set(String::new()),
-                    _ => {}
-                }
-            }
-            button { 
-                class: "inbox-button",
-                onclick: move |_| create_backlog_fn(), "Create Backlog" 
-            }
-            // button {
-            //     class: "inbox-button",
-            //     onclick: move |_| delete_backlog_fn(), "Delete Backlog"
-            // }
+            for (index, backlog) in backlogs.read().clone().into_iter().enumerate() {
+                div {
+                    key: "{backlog.id.0}",
+                    class: "flex items-center gap-2",
+                    input {
+                        class: "inbox-input flex-1",
+                        r#type: "text",
+                        value: "{backlog.name}",
+                        onchange: {
+                            let backlog = backlog.clone();
+                            move |evt: FormEvent| {
+                                if !evt.value().trim().is_empty() {
+                                    rename_backlog_fn(Backlog { name: evt.value().trim().to_string(), ..backlog.clone() });
+                                }
+                            }
+                        },
+                    }
+                    if backlog.archived_at.is_some() {
+                        span { class: "inbox-estimate", "Archived" }
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move up",
+                        onclick: move |_| move_backlog_fn(index, -1),
+                        "↑"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move down",
+                        onclick: move |_| move_backlog_fn(index, 1),
+                        "↓"
+                    }
+                    if backlog.archived_at.is_some() {
+                        button {
+                            class: "button",
+                            "data-style": "ghost",
+                            onclick: move |_| restore_backlog_fn(backlog.id),
+                            "Restore"
+                        }
+                    } else {
+                        button {
+                            class: "button",
+                            "data-style": "ghost",
+                            onclick: move |_| remove_backlog_fn(backlog.id, BacklogRemoval::Archive),
+                            "Archive"
+                        }
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| removing.set(Some(backlog.id)),
+                        "Delete"
+                    }
+                }
+                if removing() == Some(backlog.id) {
+                    BacklogRemovalOptions {
+                        backlog: backlog.clone(),
+                        others: backlogs.read().iter().filter(|b| b.id != backlog.id && b.archived_at.is_none()).cloned().collect::<Vec<_>>(),
+                        on_remove: move |removal| remove_backlog_fn(backlog.id, removal),
+                        on_cancel: move |_| removing.set(None),
+                    }
+                }
+            }
+            div {
+                class: "flex mt-2",
+                input {
+                    class: "inbox-input flex-1",
+                    r#type: "text",
+                    placeholder: "Backlog name",
+                    value: "{new_backlog}",
+                    oninput: move |evt| new_backlog.set(evt.value()),
+                    onkeydown: move |evt| match evt.key() {
+                        Key::Enter => create_backlog_fn(),
+                        Key::Escape => new_backlog.set(String::new()),
+                        _ => {}
+                    }
+                }
+                button {
+                    class: "inbox-button",
+                    onclick: move |_| create_backlog_fn(), "Create Backlog"
+                }
+            }
+        }
+    }
+}
+
+/// Asks what should happen to the tasks of a backlog that is being deleted.
+#[component]
+fn BacklogRemovalOptions(
+    backlog: Backlog,
+    others: Vec<Backlog>,
+    on_remove: EventHandler<BacklogRemoval>,
+    on_cancel: EventHandler<()>,
+) -> Element {
+    // Where the tasks go; `None` is the inbox
+    let mut target: Signal<Option<Id>> = use_signal(|| None);
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 my-2 p-2 border border-gray-400",
+            "What should happen to the tasks in {backlog.name}?"
+            div {
+                class: "flex items-center gap-2",
+                select {
+                    class: "inbox-assignee",
+                    aria_label: "Move tasks to",
+                    onchange: {
+                        let others = others.clone();
+                        move |evt: FormEvent| {
+                            target.set(others.iter().find(|b| b.id.0.to_string() == evt.value()).map(|b| b.id));
+                        }
+                    },
+                    option { value: "", selected: target().is_none(), "Inbox" }
+                    for other in others {
+                        option {
+                            key: "{other.id.0}",
+                            value: "{other.id.0}",
+                            selected: target() == Some(other.id),
+                            "{other.name}"
+                        }
+                    }
+                }
+                button {
+                    class: "button",
+                    "data-style": "outline",
+                    onclick: move |_| on_remove.call(BacklogRemoval::MoveTasks(target())),
+                    "Move tasks and delete"
+                }
+            }
+            div {
+                class: "flex gap-2",
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| on_remove.call(BacklogRemoval::DeleteTasks),
+                    "Delete with its tasks"
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| on_remove.call(BacklogRemoval::Archive),
+                    "Archive instead"
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+            }
         }
     }
 }
@@ -0,0 +1,152 @@
+use dioxus::prelude::*;
+use chrono::NaiveDate;
+use crate::backend::server;
+use crate::backend::model::{Backlog, Id, Role};
+use crate::backend::validation::error_message;
+use super::undo::{TASKS_VERSION, show_undo_toast};
+
+/// What to do with all the selected tasks.
+#[derive(Clone, PartialEq)]
+enum BulkAction {
+    Complete(bool),
+    Delete,
+    Schedule(Option<NaiveDate>),
+    MoveToBacklog(Option<Id>),
+    SetRole(Option<Id>),
+    SetFlags { important: Option<bool>, urgent: Option<bool> },
+}
+
+impl BulkAction {
+    async fn run(self, task_ids: Vec<Id>) -> Result<(), ServerFnError> {
+        match self {
+            BulkAction::Complete(completed) => server::complete_tasks(task_ids, completed).await.map(|_| ()),
+            BulkAction::Delete => server::delete_tasks(task_ids).await,
+            BulkAction::Schedule(date) => server::schedule_tasks(task_ids, date).await.map(|_| ()),
+            BulkAction::MoveToBacklog(backlog) => server::move_tasks_to_backlog(task_ids, backlog).await.map(|_| ()),
+            BulkAction::SetRole(role) => server::set_tasks_role(task_ids, role).await.map(|_| ()),
+            BulkAction::SetFlags { important, urgent } => server::set_tasks_flags(task_ids, important, urgent).await.map(|_| ()),
+        }
+    }
+
+    fn done_message(&self, count: usize) -> String {
+        let what = match self {
+            BulkAction::Complete(true) => "completed",
+            BulkAction::Complete(false) => "reopened",
+            BulkAction::Delete => "deleted",
+            BulkAction::Schedule(_) | BulkAction::MoveToBacklog(_) => "moved",
+            BulkAction::SetRole(_) | BulkAction::SetFlags { .. } => "updated",
+        };
+        format!("{} tasks {}", count, what)
+    }
+}
+
+/// Toolbar for changing all selected tasks of a list at once. Clears the selection when done.
+#[component]
+pub fn BulkActions(selected: Signal<Vec<Id>>) -> Element {
+    let mut roles: Signal<Vec<Role>> = use_signal(|| vec![]);
+    let mut backlogs: Signal<Vec<Backlog>> = use_signal(|| vec![]);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    use_effect(move || {
+        spawn(async move {
+            match server::get_roles().await {
+                Ok(fetched) => roles.set(fetched),
+                Err(e) => eprintln!("Failed to fetch roles: {}", e),
+            }
+            match server::get_backlogs().await {
+                Ok(fetched) => backlogs.set(fetched.into_iter().filter(|b| b.archived_at.is_none()).collect()),
+                Err(e) => eprintln!("Failed to fetch backlogs: {}", e),
+            }
+        });
+    });
+
+    let run_fn = move |action: BulkAction| {
+        let task_ids = selected.read().clone();
+        if task_ids.is_empty() {
+            return;
+        }
+        spawn(async move {
+            let message = action.done_message(task_ids.len());
+            match action.run(task_ids).await {
+                Ok(()) => {
+                    selected.set(vec![]);
+                    error.set(None);
+                    show_undo_toast(message);
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+
+    let count = selected.read().len();
+
+    rsx! {
+        div {
+            class: "bulk-actions",
+            span { class: "bulk-count", "{count} selected" }
+            button { class: "button", "data-style": "outline", onclick: move |_| run_fn(BulkAction::Complete(true)), "Complete" }
+            button { class: "button", "data-style": "outline", onclick: move |_| run_fn(BulkAction::Complete(false)), "Reopen" }
+            input {
+                class: "inbox-input",
+                r#type: "date",
+                aria_label: "Move to date",
+                onchange: move |evt| {
+                    if let Ok(date) = evt.value().parse::<NaiveDate>() {
+                        run_fn(BulkAction::Schedule(Some(date)));
+                    }
+                }
+            }
+            button { class: "button", "data-style": "outline", onclick: move |_| run_fn(BulkAction::Schedule(None)), "Unschedule" }
+            select {
+                class: "inbox-assignee",
+                aria_label: "Move to backlog",
+                onchange: move |evt| {
+                    let backlog = backlogs.read().iter().find(|b| b.id.0.to_string() == evt.value()).map(|b| b.id);
+                    run_fn(BulkAction::MoveToBacklog(backlog));
+                },
+                option { value: "", disabled: true, selected: true, "Move to…" }
+                option { value: "inbox", "Inbox" }
+                for backlog in backlogs.read().clone() {
+                    option { key: "{backlog.id.0}", value: "{backlog.id.0}", "{backlog.name}" }
+                }
+            }
+            select {
+                class: "inbox-assignee",
+                aria_label: "Set role",
+                onchange: move |evt| {
+                    let role = roles.read().iter().find(|r| r.id.0.to_string() == evt.value()).map(|r| r.id);
+                    run_fn(BulkAction::SetRole(role));
+                },
+                option { value: "", disabled: true, selected: true, "Role…" }
+                option { value: "none", "No role" }
+                for role in roles.read().clone() {
+                    option { key: "{role.id.0}", value: "{role.id.0}", "{role.name}" }
+                }
+            }
+            button {
+                class: "button",
+                "data-style": "outline",
+                onclick: move |_| run_fn(BulkAction::SetFlags { important: Some(true), urgent: None }),
+                "Important"
+            }
+            button {
+                class: "button",
+                "data-style": "outline",
+                onclick: move |_| run_fn(BulkAction::SetFlags { important: None, urgent: Some(true) }),
+                "Urgent"
+            }
+            button {
+                class: "button",
+                "data-style": "outline",
+                onclick: move |_| run_fn(BulkAction::SetFlags { important: Some(false), urgent: Some(false) }),
+                "Clear flags"
+            }
+            button { class: "button", "data-style": "destructive", onclick: move |_| run_fn(BulkAction::Delete), "Delete" }
+            button { class: "button", "data-style": "ghost", onclick: move |_| selected.set(vec![]), "Clear selection" }
+            if let Some(message) = error() {
+                div { class: "query-error", "{message}" }
+            }
+        }
+    }
+}
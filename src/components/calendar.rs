@@ -2,26 +2,38 @@ use dioxus::prelude::*;
 use dioxus_primitives::calendar::{
     Calendar, CalendarContext, CalendarDate, CalendarGrid, CalendarHeader, CalendarNavigation, CalendarNextMonthButton, CalendarPreviousMonthButton
 };
-use chrono::{Datelike, Utc, NaiveDate};
+use chrono::{Datelike, NaiveDate};
+use super::preferences::PREFERENCES;
 
 
 #[component]
 pub fn DatePicker(on_selection_change: EventHandler<NaiveDate>) -> Element {
     let mut view_date = use_signal(|| {
-        let today = Utc::now().date_naive();
+        let today = PREFERENCES.read().today();
         let year: i32 = today.year() as i32;
         let month: u32 = today.month() as u32;
         let day: u32 = today.day() as u32;   
         CalendarDate::new(year, month, day)
     });
     let mut selected_date: Signal<Option<CalendarDate>> = use_signal(|| {
-        let today = Utc::now().date_naive();
+        let today = PREFERENCES.read().today();
         let year: i32 = today.year() as i32;
         let month: u32 = today.month() as u32;
         let day: u32 = today.day() as u32;
         Some(CalendarDate::new(year, month, day))
     });
 
+    let time_zone = use_memo(move || PREFERENCES.read().time_zone);
+
+    // Follow the user's time zone, which is only known once preferences are fetched
+    use_effect(move || {
+        let _ = time_zone();
+        let today = PREFERENCES.peek().today();
+        let today = CalendarDate::new(today.year(), today.month(), today.day());
+        view_date.set(today);
+        selected_date.set(Some(today));
+    });
+
      rsx! {
         div { class: "calendar-example", style: "padding: 20px;",
             div { class: "calendar",
@@ -81,11 +93,11 @@ fn MonthTitle() -> Element {
                 view_date.month = e.value().parse().unwrap_or(view_date.month);
                 calendar.set_view_date(view_date);
             },
-            for (i, month) in CalendarDate::MONTH_ABBREVIATIONS.iter().enumerate() {
+            for month in 1..=12u32 {
                 option {
-                    value: i + 1,
-                    selected: calendar.view_date().month == (i as u32 + 1),
-                    "{month}"
+                    value: month,
+                    selected: calendar.view_date().month == month,
+                    "{PREFERENCES.read().locale.month_short(month)}"
                 }
             }
         }
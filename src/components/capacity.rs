@@ -0,0 +1,188 @@
+use dioxus::prelude::*;
+use chrono::{NaiveDate, Weekday};
+use crate::backend::server;
+use crate::backend::model::{Capacity, RebalanceSuggestion, TaskPatch, capacity_on, format_minutes};
+use crate::backend::validation::is_conflict;
+use dioxus_primitives::dialog::{DialogDescription, DialogTitle};
+use super::undo::TASKS_VERSION;
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+];
+
+/// Planned versus available time of a day, highlighted when the day is overbooked.
+#[component]
+pub fn CapacityIndicator(planned: i32, available: i32) -> Element {
+    let class = if planned > available { "capacity overloaded" } else { "capacity" };
+
+    rsx! {
+        div {
+            class: "{class}",
+            "{format_minutes(planned)} / {format_minutes(available)}"
+            if planned > available {
+                " ⚠ overbooked by {format_minutes(planned - available)}"
+            }
+        }
+    }
+}
+
+#[component]
+pub fn CapacityManager(selected_date: NaiveDate, capacities: Signal<Vec<Capacity>>) -> Element {
+    let refetch_fn = move || {
+        spawn(async move {
+            match server::get_capacities().await {
+                Ok(fetched) => capacities.set(fetched),
+                Err(e) => eprintln!("Failed to fetch capacities: {}", e),
+            }
+        });
+    };
+
+    let set_weekday_fn = move |day: i32, value: String| {
+        let Ok(minutes) = value.trim().parse::<i32>() else {
+            return;
+        };
+        spawn(async move {
+            match server::set_weekday_capacity(day, minutes).await {
+                Ok(_) => refetch_fn(),
+                Err(e) => eprintln!("Failed to set weekday capacity: {}", e),
+            }
+        });
+    };
+
+    let set_date_fn = move |value: String| {
+        let minutes = value.trim().parse::<i32>().ok();
+        spawn(async move {
+            match server::set_date_capacity(selected_date, minutes).await {
+                Ok(_) => refetch_fn(),
+                Err(e) => eprintln!("Failed to set date capacity: {}", e),
+            }
+        });
+    };
+
+    let date_override = capacities
+        .read()
+        .iter()
+        .find(|c| c.date == Some(selected_date))
+        .map(|c| c.minutes.to_string())
+        .unwrap_or_default();
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Capacity (minutes per day)"
+        }
+        DialogDescription {
+            for (i, weekday) in WEEKDAYS.iter().enumerate() {
+                label {
+                    key: "{weekday}",
+                    class: "flex items-center gap-2",
+                    span { class: "w-12", "{weekday}" }
+                    input {
+                        class: "inbox-input",
+                        r#type: "number",
+                        min: "0",
+                        step: "30",
+                        value: "{weekday_minutes(&capacities.read(), i)}",
+                        onchange: move |evt| set_weekday_fn(i as i32, evt.value()),
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2 mt-4",
+                span { "Only on {selected_date}" }
+                input {
+                    class: "inbox-input",
+                    r#type: "number",
+                    min: "0",
+                    step: "30",
+                    placeholder: "{capacity_on(&capacities.read(), selected_date)}",
+                    value: "{date_override}",
+                    onchange: move |evt| set_date_fn(evt.value()),
+                }
+            }
+        }
+    }
+}
+
+fn weekday_minutes(capacities: &[Capacity], weekday: usize) -> String {
+    capacities
+        .iter()
+        .find(|c| c.weekday == Some(weekday as i32))
+        .map(|c| c.minutes.to_string())
+        .unwrap_or_default()
+}
+
+/// Lists suggested moves for the week, each of which can be applied on its own.
+#[component]
+pub fn RebalancePanel(week_start: NaiveDate) -> Element {
+    let mut suggestions: Signal<Vec<RebalanceSuggestion>> = use_signal(|| vec![]);
+    let mut loaded = use_signal(|| false);
+
+    use_effect(use_reactive((&week_start,), move |(week_start,)| {
+        spawn(async move {
+            match server::suggest_rebalance(week_start).await {
+                Ok(fetched) => suggestions.set(fetched),
+                Err(e) => eprintln!("Failed to fetch rebalance suggestions: {}", e),
+            }
+            loaded.set(true);
+        });
+    }));
+
+    let apply_fn = move |suggestion: RebalanceSuggestion| {
+        spawn(async move {
+            let patch = TaskPatch { scheduled_date: Some(Some(suggestion.to)), ..TaskPatch::new(&suggestion.task) };
+            match server::patch_task(patch).await {
+                Ok(_) => {
+                    suggestions.write().retain(|s| s.task.id != suggestion.task.id);
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) if is_conflict(&e) => {
+                    // The task changed since the suggestion was made, so it may no longer apply
+                    suggestions.write().retain(|s| s.task.id != suggestion.task.id);
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to move task {}: {}", suggestion.task.id.0, e),
+            }
+        });
+    };
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Rebalance week"
+        }
+        DialogDescription {
+            if loaded() && suggestions.read().is_empty() {
+                "Nothing to rebalance, no day in this week is overbooked."
+            }
+            for suggestion in suggestions.read().clone() {
+                div {
+                    key: "{suggestion.task.id.0}",
+                    class: "flex items-center gap-2",
+                    span {
+                        class: "flex-1",
+                        "{describe_suggestion(&suggestion)}"
+                    }
+                    button {
+                        class: "inbox-button",
+                        onclick: {
+                            let suggestion = suggestion.clone();
+                            move |_| apply_fn(suggestion.clone())
+                        },
+                        "Move"
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn describe_suggestion(suggestion: &RebalanceSuggestion) -> String {
+    format!(
+        "{} ({}): {} → {}",
+        suggestion.task.title,
+        format_minutes(suggestion.task.estimate_minutes.unwrap_or_default()),
+        suggestion.from.format("%a %d"),
+        suggestion.to.format("%a %d"),
+    )
+}
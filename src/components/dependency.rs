@@ -0,0 +1,157 @@
+use dioxus::prelude::*;
+use crate::backend::server;
+use crate::backend::model::{BacklogFilter, DateFilter, Id, Task, TaskFilter, TaskLinks, schedule_warnings};
+use super::undo::{TASKS_VERSION, is_undo_shortcut};
+
+const CANDIDATE_LIMIT: i64 = 5;
+
+/// Toast message after a task was rescheduled, warning when it now lands before a task it
+/// waits for.
+pub async fn rescheduled_message(task_id: Id, fallback: &str) -> String {
+    match server::get_schedule_warnings(task_id).await {
+        Ok(warnings) if !warnings.is_empty() => format!("⚠ {}", warnings.join("; ")),
+        Ok(_) => fallback.to_string(),
+        Err(e) => {
+            eprintln!("Failed to check schedule of task {}: {}", task_id.0, e);
+            fallback.to_string()
+        },
+    }
+}
+
+/// Blocked-by and blocks links of a task, with a search to add blockers.
+#[component]
+pub fn DependencyEditor(task: Task) -> Element {
+    let mut links: Signal<TaskLinks> = use_signal(TaskLinks::default);
+    let mut search = use_signal(|| String::new());
+    let mut candidates: Signal<Vec<Task>> = use_signal(|| vec![]);
+    let mut link_error: Signal<Option<String>> = use_signal(|| None);
+    let task_id = task.id;
+
+    let fetch_links_fn = move || {
+        spawn(async move {
+            match server::get_task_links(task_id).await {
+                Ok(fetched) => links.set(fetched),
+                Err(e) => eprintln!("Failed to fetch links of task {}: {}", task_id.0, e),
+            }
+        });
+    };
+
+    use_hook(|| {
+        fetch_links_fn();
+    });
+
+    let search_fn = move |text: String| {
+        search.set(text.clone());
+        if text.trim().is_empty() {
+            candidates.set(vec![]);
+            return;
+        }
+        spawn(async move {
+            let filter = TaskFilter {
+                scheduled: DateFilter::Any,
+                backlog: BacklogFilter::Any,
+                completed: Some(false),
+                text: Some(text),
+                limit: Some(CANDIDATE_LIMIT + 1),
+                ..Default::default()
+            };
+            match server::get_tasks(filter).await {
+                Ok(mut fetched) => {
+                    fetched.retain(|t| t.id != task_id);
+                    fetched.truncate(CANDIDATE_LIMIT as usize);
+                    candidates.set(fetched);
+                },
+                Err(e) => eprintln!("Failed to search tasks: {}", e),
+            }
+        });
+    };
+
+    let add_blocker_fn = move |blocker_id: Id| {
+        spawn(async move {
+            match server::add_task_dependency(blocker_id, task_id).await {
+                Ok(_) => {
+                    link_error.set(None);
+                    search.set(String::new());
+                    candidates.set(vec![]);
+                    fetch_links_fn();
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => link_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let remove_link_fn = move |blocker_id: Id, blocked_id: Id| {
+        spawn(async move {
+            match server::remove_task_dependency(blocker_id, blocked_id).await {
+                Ok(_) => {
+                    fetch_links_fn();
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to remove link: {}", e),
+            }
+        });
+    };
+
+    let warnings = schedule_warnings(&task, &links.read());
+
+    rsx! {
+        h3 { class: "mt-4", "Blocked by" }
+        for blocker in links.read().blocked_by.clone() {
+            div {
+                key: "{blocker.id.0}",
+                class: if blocker.completed { "flex items-center gap-2 line-through" } else { "flex items-center gap-2" },
+                span { class: "flex-1", "{blocker.title}" }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| remove_link_fn(blocker.id, task_id),
+                    "Remove"
+                }
+            }
+        }
+        input {
+            class: "inbox-input",
+            r#type: "text",
+            placeholder: "Find a task this one waits for",
+            value: "{search}",
+            oninput: move |evt| search_fn(evt.value()),
+            onkeydown: move |evt| {
+                if is_undo_shortcut(&evt) {
+                    evt.stop_propagation();
+                }
+            },
+        }
+        for candidate in candidates.read().clone() {
+            button {
+                key: "{candidate.id.0}",
+                class: "button block",
+                "data-style": "ghost",
+                onclick: move |_| add_blocker_fn(candidate.id),
+                "{candidate.title}"
+            }
+        }
+        if let Some(message) = link_error() {
+            div { class: "query-error", "{message}" }
+        }
+        if !links.read().blocks.is_empty() {
+            h3 { class: "mt-4", "Blocks" }
+            for dependent in links.read().blocks.clone() {
+                div {
+                    key: "{dependent.id.0}",
+                    class: "flex items-center gap-2",
+                    span { class: "flex-1", "{dependent.title}" }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| remove_link_fn(task_id, dependent.id),
+                        "Remove"
+                    }
+                }
+            }
+        }
+        for warning in warnings {
+            div { class: "query-error", "⚠ {warning}" }
+        }
+    }
+}
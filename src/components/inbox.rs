@@ -9,7 +9,7 @@ pub fn InboxApp() -> Element {
         div {
             class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
             "Inbox",
-            ItemList { filter: TaskFilter {scheduled_date: None, backlog_id: None} } 
+            ItemList { filter: TaskFilter::default() } 
         }
     }
 }
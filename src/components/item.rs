@@ -1,39 +1,143 @@
 use dioxus::prelude::*;
 use crate::backend::server;
-use crate::backend::model::{Task, Id, TaskFilter};
+use crate::backend::model::{BacklogColumn, BacklogFilter, Task, TaskPatch, Id, TaskFilter, Member, TaskHistoryEntry, column_of, format_minutes};
+use dioxus_primitives::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+use crate::backend::quick_add::{parse_quick_add, quick_add_chips};
+use crate::backend::validation::{error_message, is_conflict};
+use super::undo::{TASKS_VERSION, is_undo_shortcut, show_undo_toast};
+use super::tag::{TagEditor, load_task_tags};
+use super::dependency::{DependencyEditor, rescheduled_message};
+use super::project::ProjectSelect;
+use super::bulk::BulkActions;
+use super::preferences::PREFERENCES;
+use std::collections::HashMap;
 
-static DRAGGING_ITEM: GlobalSignal<Option<Task>> = Signal::global(|| None);
+pub(super) static DRAGGING_ITEM: GlobalSignal<Option<Task>> = Signal::global(|| None);
 static DROPPED_ITEM: GlobalSignal<Option<Task>> = Signal::global(|| None);
+/// Tasks of a list fetched by its parent together with those of other lists, e.g. the days of a
+/// week, so each list doesn't need calls of its own.
+#[derive(Clone, PartialEq, Default)]
+pub struct PreloadedTasks {
+    pub tasks: Vec<Task>,
+    /// Those of the tasks still waiting on another one.
+    pub blocked: Vec<Id>,
+}
+
+/// How each list shows completed tasks, keyed by its filter, so the choice survives remounts.
+static COMPLETED_DISPLAY: GlobalSignal<HashMap<String, CompletedDisplay>> = Signal::global(HashMap::new);
+
+/// How a list shows the tasks that are ticked off.
+#[derive(Clone, Copy, PartialEq, Default)]
+enum CompletedDisplay {
+    #[default]
+    Show,
+    Dim,
+    /// After all open tasks.
+    Sink,
+    Hide,
+}
+
+impl CompletedDisplay {
+    const ALL: [CompletedDisplay; 4] = [CompletedDisplay::Show, CompletedDisplay::Dim, CompletedDisplay::Sink, CompletedDisplay::Hide];
+
+    fn label(&self) -> &'static str {
+        match self {
+            CompletedDisplay::Show => "Show done",
+            CompletedDisplay::Dim => "Dim done",
+            CompletedDisplay::Sink => "Done last",
+            CompletedDisplay::Hide => "Hide done",
+        }
+    }
+
+    /// The tasks of a list in the order they are shown.
+    fn apply(&self, tasks: &[Task]) -> Vec<Task> {
+        let mut shown: Vec<Task> = match self {
+            CompletedDisplay::Hide => tasks.iter().filter(|t| !t.completed).cloned().collect(),
+            _ => tasks.to_vec(),
+        };
+        if *self == CompletedDisplay::Sink {
+            shown.sort_by_key(|t| t.completed);
+        }
+        shown
+    }
+}
 
 #[component]
-pub fn ItemList(filter: TaskFilter) -> Element {
+pub fn ItemList(
+    filter: TaskFilter,
+    #[props(default)] members: Vec<Member>,
+    #[props(default)] on_tasks_change: Option<EventHandler<Vec<Task>>>,
+    /// Status columns to lay the tasks out in, as on a backlog's board.
+    #[props(default)] columns: Vec<BacklogColumn>,
+    /// The tasks to show, when the parent fetched them; the list then doesn't fetch its own.
+    #[props(default)] preloaded: Option<PreloadedTasks>,
+) -> Element {
     let mut new_task = use_signal(|| String::new());
+    let mut create_error: Signal<Option<String>> = use_signal(|| None);
     let mut tasks = use_signal(|| vec![]);
-    let day = filter.scheduled_date;
-    let backlog_id = filter.backlog_id;
-    use_hook(|| {
+    let mut blocked: Signal<Vec<Id>> = use_signal(|| vec![]);
+    // Tasks picked for a bulk action, and the one clicked last, which shift-clicks extend from
+    let mut selected: Signal<Vec<Id>> = use_signal(|| vec![]);
+    let mut select_anchor: Signal<Option<Id>> = use_signal(|| None);
+    // Where tasks created in or dropped on this list go, if the filter pins that down
+    let target = filter.target();
+    // Lists showing every backlog leave a dropped task's backlog alone
+    let keeps_backlog = filter.backlog == BacklogFilter::Any;
+    // Lists are told apart by their filter when remembering how they show completed tasks
+    let display_key = serde_json::to_string(&filter).unwrap_or_default();
+    // Refetch when tasks were changed elsewhere, e.g. by undo
+    let fetch_filter = filter.clone();
+    use_effect(use_reactive((&preloaded,), move |(preloaded,)| {
+        if let Some(preloaded) = preloaded {
+            selected.write().retain(|id| preloaded.tasks.iter().any(|t| t.id == *id));
+            tasks.set(preloaded.tasks);
+            blocked.set(preloaded.blocked);
+            return;
+        }
+        let _ = TASKS_VERSION.read();
+        let filter = fetch_filter.clone();
         spawn(async move {
             match server::get_tasks(filter).await {
-                Ok(fetched) => tasks.set(fetched),
+                Ok(fetched) => {
+                    let ids: Vec<Id> = fetched.iter().map(|t| t.id).collect();
+                    selected.write().retain(|id| ids.contains(id));
+                    load_task_tags(ids.clone());
+                    tasks.set(fetched);
+                    match server::get_blocked_tasks(ids).await {
+                        Ok(fetched) => blocked.set(fetched),
+                        Err(e) => eprintln!("Failed to fetch blocked tasks: {}", e),
+                    }
+                },
                 Err(e) => eprintln!("Failed to fetch tasks: {}", e),
             }
         });
-    });
+    }));
 
     let create_task_fn = {
         move || {
             let title = new_task.read().clone();
+            let Some((day, backlog_id)) = target else {
+                return;
+            };
             if title.is_empty() {
                 return;
             }
             spawn({
                 async move {
-                    match server::create_task(title, day, backlog_id).await {
+                    let draft = parse_quick_add(&title, PREFERENCES.read().today());
+                    match server::create_task(draft, day, backlog_id).await {
                         Ok(task) => {
-                            tasks.write().push(task);
+                            show_undo_toast(format!("Created \"{}\"", task.title));
+                            // Tokens like "tomorrow" or #backlog may have sent it elsewhere
+                            if task.scheduled_date == day && (keeps_backlog || task.backlog_id == backlog_id) {
+                                tasks.write().push(task);
+                            }
                             new_task.set(String::new());
+                            create_error.set(None);
+                            // Let other lists, e.g. saved views, pick up the change
+                            *TASKS_VERSION.write() += 1;
                         },
-                        Err(e) => eprintln!("Failed to create task: {}", e),
+                        Err(e) => create_error.set(Some(error_message(&e))),
                     }
                 }
             });
@@ -46,7 +150,11 @@ pub fn ItemList(filter: TaskFilter) -> Element {
             spawn({
                 async move {
                     match server::delete_task(id).await {
-                        Ok(_) => tasks.write().retain(|t| t.id != id),
+                        Ok(_) => {
+                            tasks.write().retain(|t| t.id != id);
+                            show_undo_toast("Task deleted");
+                            *TASKS_VERSION.write() += 1;
+                        },
                         Err(e) => eprintln!("Failed to delete task: {}", e),
                     }
                 }
@@ -54,72 +162,249 @@ pub fn ItemList(filter: TaskFilter) -> Element {
         }
     };
 
-    let update_task_fn = move |task: Task| {
+    // Sends only what changed, so edits made elsewhere to other fields survive
+    let patch_task_fn = move |patch: TaskPatch, message: &'static str| {
+        let rescheduled = patch.scheduled_date.is_some();
         spawn(async move {
-            let id = task.id.0;
-            match server::update_task(task).await {
-                Ok(_) => {},
-                Err(e) => eprintln!("Failed to update task {}: {}", id, e),
+            match server::patch_task(patch).await {
+                Ok(patched) => {
+                    if let Some(t) = tasks.write().iter_mut().find(|t| t.id == patched.id) {
+                        *t = patched.clone();
+                    }
+                    if rescheduled {
+                        show_undo_toast(rescheduled_message(patched.id, message).await);
+                    } else {
+                        show_undo_toast(message);
+                    }
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) if is_conflict(&e) => {
+                    show_undo_toast(error_message(&e));
+                    // Fetch the task as it is now
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to update task: {}", e),
             }
         });
     };
 
-   use_effect(move || {
+    let select_fn = move |task_id: Id, click: SelectClick| {
+        let order: Vec<Id> = tasks.read().iter().map(|t| t.id).collect();
+        let anchor = select_anchor.read().and_then(|anchor| order.iter().position(|id| *id == anchor));
+        let clicked = order.iter().position(|id| *id == task_id);
+        let mut selection = selected.write();
+        match (click, anchor, clicked) {
+            (SelectClick::Range, Some(from), Some(to)) => {
+                for id in &order[from.min(to)..=from.max(to)] {
+                    if !selection.contains(id) {
+                        selection.push(*id);
+                    }
+                }
+            },
+            _ => match selection.iter().position(|id| *id == task_id) {
+                Some(index) => {
+                    selection.remove(index);
+                },
+                None => selection.push(task_id),
+            },
+        }
+        select_anchor.set(Some(task_id));
+    };
+
+    use_effect(move || {
+        let current = tasks.read().clone();
+        if let Some(handler) = on_tasks_change {
+            handler.call(current);
+        }
+    });
+
+    use_effect(move || {
         if let Some(task) = DROPPED_ITEM.read().clone() {
-            tasks.write().retain(|t| !(t.id == task.id && (task.scheduled_date != day || task.backlog_id != backlog_id)));
+            tasks.write().retain(|t| t.id != task.id || filter.matches(&task));
         }
     });
 
+    // Dropping on a column sets the task's status, and brings it into this list if needed
+    let move_to_column_fn = move |column_id: Id| {
+        let Some(dragged) = DRAGGING_ITEM.read().clone() else {
+            return;
+        };
+        let backlog_id = match target {
+            Some((_, backlog_id)) if !keeps_backlog => backlog_id,
+            _ => dragged.backlog_id,
+        };
+        let patch = TaskPatch { status_id: Some(Some(column_id)), backlog_id: Some(backlog_id), ..TaskPatch::new(&dragged) };
+        let task = patch.apply(&dragged);
+        if task == dragged {
+            return;
+        }
+        let mut list = tasks.write();
+        match list.iter().position(|t| t.id == task.id) {
+            Some(index) => list[index] = task.clone(),
+            None => list.push(task.clone()),
+        }
+        drop(list);
+        *DROPPED_ITEM.write() = Some(task);
+        patch_task_fn(patch, "Task moved");
+    };
+
+    let display = COMPLETED_DISPLAY.read().get(&display_key).copied().unwrap_or_default();
+    let shown = display.apply(&tasks.read());
+    let dim_completed = display == CompletedDisplay::Dim;
+
+    let board: Vec<(BacklogColumn, Vec<Task>)> = columns
+        .iter()
+        .map(|column| {
+            let in_column = shown.iter().filter(|t| column_of(t, &columns) == Some(column.id)).cloned().collect();
+            (column.clone(), in_column)
+        })
+        .collect();
+
+    let chips = {
+        let preferences = PREFERENCES.read();
+        quick_add_chips(&parse_quick_add(&new_task.read(), preferences.today()), &preferences)
+    };
+
     rsx! {
         div {
-            class: "inbox-component",
+            class: if selected.read().is_empty() { "inbox-component" } else { "inbox-component selecting" },
 
-            div {
-                class: "flex",
-                input {
-                    class: "inbox-input flex-1",
-                    r#type: "text",
-                    placeholder: "Enter a task",
-                    value: "{new_task}",
-                    oninput: move |evt| new_task.set(evt.value()),
-                    onkeydown: move |evt| match evt.key() {
-                        Key::Enter => create_task_fn(),
-                        Key::Escape => new_task.set(String::new()),
-                        _ => {}
-                    }
-                }
-                button { 
-                    class: "inbox-button",
-                    onclick: move |_| create_task_fn(), "Add" 
-                }
-            }
-
-            div {
-                class: "p-4 border border-zinc-700 rounded w-1/2 bg-zinc-800 shadow-md",
-                ondragover: move |e| e.prevent_default(),
-                ondrop: move |_| {
-                    if let Some(mut task) = DRAGGING_ITEM.read().clone() {
-                        if task.scheduled_date != day {
-                            task.scheduled_date = day;
-                            let t = task.clone();
-                            spawn(async move {server::update_task(t).await.unwrap();});
-                            tasks.write().push(task.clone());
-                            *DROPPED_ITEM.write() = Some(task.clone());
-                        } else if task.backlog_id != backlog_id {
-                            task.backlog_id = backlog_id;
-                            let t = task.clone();
-                            spawn(async move {server::update_task(t).await.unwrap();});
-                            tasks.write().push(task.clone());
-                            *DROPPED_ITEM.write() = Some(task.clone());
-                        }
+            select {
+                class: "inbox-assignee completed-display",
+                aria_label: "Completed tasks",
+                onchange: move |evt| {
+                    if let Some(mode) = CompletedDisplay::ALL.into_iter().find(|m| m.label() == evt.value()) {
+                        COMPLETED_DISPLAY.write().insert(display_key.clone(), mode);
                     }
                 },
-                for task in tasks.read().clone().iter() {
-                    Item {
-                        key: "{task.id.0}",
-                        task: task.clone(),
-                        on_delete: delete_task_fn.clone(),
-                        on_update: update_task_fn.clone(),
+                for mode in CompletedDisplay::ALL {
+                    option { value: mode.label(), selected: mode == display, "{mode.label()}" }
+                }
+            }
+
+            if target.is_some() {
+                div {
+                    class: "flex",
+                    input {
+                        class: "inbox-input flex-1",
+                        r#type: "text",
+                        placeholder: "Enter a task, e.g. Pay rent next fri #home ! ~15m",
+                        value: "{new_task}",
+                        oninput: move |evt| new_task.set(evt.value()),
+                        onkeydown: move |evt| {
+                            if is_undo_shortcut(&evt) {
+                                evt.stop_propagation();
+                                return;
+                            }
+                            match evt.key() {
+                                Key::Enter => create_task_fn(),
+                                Key::Escape => new_task.set(String::new()),
+                                _ => {}
+                            }
+                        }
+                    }
+                    button { 
+                        class: "inbox-button",
+                        onclick: move |_| create_task_fn(), "Add" 
+                    }
+                }
+                if let Some(message) = create_error() {
+                    div { class: "query-error", "{message}" }
+                }
+                if !chips.is_empty() {
+                    div {
+                        class: "quick-add-chips",
+                        for chip in chips {
+                            span { class: "quick-add-chip", "{chip}" }
+                        }
+                    }
+                }
+            }
+
+            if !selected.read().is_empty() {
+                BulkActions { selected }
+            }
+
+            if !board.is_empty() {
+                div {
+                    class: "kanban-board",
+                    for (column, column_tasks) in board {
+                        div {
+                            key: "{column.id.0}",
+                            class: if column.over_limit(column_tasks.len()) { "kanban-column over-limit" } else { "kanban-column" },
+                            ondragover: move |e| e.prevent_default(),
+                            ondrop: move |_| move_to_column_fn(column.id),
+                            div {
+                                class: "kanban-column-header",
+                                span { class: "flex-1", "{column.name}" }
+                                if let Some(limit) = column.wip_limit {
+                                    span {
+                                        title: if column.over_limit(column_tasks.len()) { "Over the WIP limit" } else { "WIP limit" },
+                                        "{column_tasks.len()} / {limit}"
+                                    }
+                                } else {
+                                    span { "{column_tasks.len()}" }
+                                }
+                            }
+                            if column.over_limit(column_tasks.len()) {
+                                div { class: "kanban-warning", "Over the WIP limit, finish something first" }
+                            }
+                            for task in column_tasks {
+                                Item {
+                                    key: "{task.id.0}",
+                                    blocked: blocked.read().contains(&task.id),
+                                    dimmed: dim_completed && task.completed,
+                                    task: task.clone(),
+                                    members: members.clone(),
+                                    on_delete: delete_task_fn.clone(),
+                                    on_patch: move |patch| patch_task_fn(patch, "Task updated"),
+                                    in_selection: selected.read().contains(&task.id),
+                                    on_select: {
+                                        let task_id = task.id;
+                                        move |click| select_fn(task_id, click)
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            } else {
+                div {
+                    class: "p-4 border border-zinc-700 rounded w-1/2 bg-zinc-800 shadow-md",
+                    ondragover: move |e| e.prevent_default(),
+                    ondrop: move |_| {
+                        let Some((day, backlog_id)) = target else {
+                            return;
+                        };
+                        if let Some(dragged) = DRAGGING_ITEM.read().clone() {
+                            if dragged.scheduled_date != day || (!keeps_backlog && dragged.backlog_id != backlog_id) {
+                                let patch = if dragged.scheduled_date != day {
+                                    TaskPatch { scheduled_date: Some(day), ..TaskPatch::new(&dragged) }
+                                } else {
+                                    TaskPatch { backlog_id: Some(backlog_id), ..TaskPatch::new(&dragged) }
+                                };
+                                let task = patch.apply(&dragged);
+                                tasks.write().push(task.clone());
+                                *DROPPED_ITEM.write() = Some(task);
+                                patch_task_fn(patch, "Task moved");
+                            }
+                        }
+                    },
+                    for task in shown.iter() {
+                        Item {
+                            key: "{task.id.0}",
+                            blocked: blocked.read().contains(&task.id),
+                            dimmed: dim_completed && task.completed,
+                            task: task.clone(),
+                            members: members.clone(),
+                            on_delete: delete_task_fn.clone(),
+                            on_patch: move |patch| patch_task_fn(patch, "Task updated"),
+                            in_selection: selected.read().contains(&task.id),
+                            on_select: {
+                                let task_id = task.id;
+                                move |click| select_fn(task_id, click)
+                            },
+                        }
                     }
                 }
             }
@@ -127,6 +412,15 @@ pub fn ItemList(filter: TaskFilter) -> Element {
     }
 }
 
+/// How a click changes which tasks of a list are selected.
+#[derive(PartialEq, Clone, Copy)]
+enum SelectClick {
+    /// The checkbox or a Ctrl/Cmd-click adds or removes one task.
+    Toggle,
+    /// A shift-click selects every task between the one clicked last and this one.
+    Range,
+}
+
 #[derive(PartialEq, Clone)]
 enum ItemState {
     Normal,
@@ -135,87 +429,355 @@ enum ItemState {
 }
 
 #[component]
-fn Item(task: Task, on_delete: EventHandler<Id>, on_update: EventHandler<Task>) -> Element {
+fn Item(
+    task: Task,
+    members: Vec<Member>,
+    on_delete: EventHandler<Id>,
+    on_patch: EventHandler<TaskPatch>,
+    #[props(default)] blocked: bool,
+    #[props(default)] dimmed: bool,
+    /// Whether the task is picked for a bulk action.
+    #[props(default)] in_selection: bool,
+    on_select: EventHandler<SelectClick>,
+) -> Element {
     let mut state = use_signal(|| ItemState::Normal);
     let mut disabled = use_signal(|| true);
     let mut title = use_signal(|| task.title.clone());
     let mut old_title = use_signal(|| task.title.clone());
+    let mut details_open = use_signal(|| false);
 
-    // Prevent moving task into closure
-    let t = task.clone();
-    use_effect(move || {
-        title.set(t.title.clone());
-    });
+    // Keep the title in sync when the task is replaced, e.g. after an undo
+    let prop_title = task.title.clone();
+    use_effect(use_reactive((&prop_title,), move |(prop_title,)| {
+        title.set(prop_title);
+    }));
 
     let apply_state_class = || match state.read().clone() {
         ItemState::Normal => "inbox-item",
         ItemState::Hovered => "inbox-item hovered",
         ItemState::Selected => "inbox-item selected",
     };
+    let completed_class = if task.completed { " completed" } else { "" };
+    let row_class = match (in_selection, dimmed) {
+        (true, true) => "item-row flex items-center gap-1 multi-selected dimmed",
+        (true, false) => "item-row flex items-center gap-1 multi-selected",
+        (false, true) => "item-row flex items-center gap-1 dimmed",
+        (false, false) => "item-row flex items-center gap-1",
+    };
 
     rsx! {
-        input {
-            r#type: "text",
-            class: "{apply_state_class()}",
-            disabled: "{disabled}",
-            value: "{title}",
-            draggable: "true",
-
-            onmouseenter: move |_| {
-                if state.read().clone() != ItemState::Selected {
-                    state.set(ItemState::Hovered);
-                    disabled.set(false);
-                }
-            },
-            onmouseleave: move |_| {
-                if state.read().clone() != ItemState::Selected {
-                    state.set(ItemState::Normal);
-                    disabled.set(true);
+        div {
+            class: row_class,
+            input {
+                r#type: "checkbox",
+                class: "item-select",
+                aria_label: "Select",
+                checked: in_selection,
+                onclick: move |evt| {
+                    evt.stop_propagation();
+                    on_select.call(if evt.modifiers().shift() { SelectClick::Range } else { SelectClick::Toggle });
                 }
-            },
-            onclick: move |_| {
-                state.set(ItemState::Selected);
-                old_title.set(title.read().clone());
-            },
-            oninput: move |evt| title.set(evt.value()),
-            onblur: {
-                let mut task = task.clone();
-                move |_| {
-                    if title.read().clone().is_empty() {
-                        on_delete.call(task.id);
-                    } else if title.read().clone() != old_title.read().clone() {
-                        task.title = title.read().clone();
-                        on_update.call(task.clone());
-                    }
-                    state.set(ItemState::Normal);
-                    disabled.set(true);
+            }
+            input {
+                r#type: "checkbox",
+                class: "item-complete",
+                aria_label: "Completed",
+                checked: task.completed,
+                onchange: {
+                    let task = task.clone();
+                    move |_| on_patch.call(TaskPatch { completed: Some(!task.completed), ..TaskPatch::new(&task) })
                 }
-            },
-            onkeydown: {
-                let mut task = task.clone();
-                move |evt| match evt.key() {
-                Key::Enter => {
-                    task.title = title.read().clone();
-                    on_update.call(task.clone());
-                    state.set(ItemState::Normal);
-                    disabled.set(true);
+            }
+            input {
+                r#type: "text",
+                class: if blocked { "{apply_state_class()}{completed_class} blocked flex-1" } else { "{apply_state_class()}{completed_class} flex-1" },
+                title: if blocked { "Blocked by an open task" } else { "" },
+                disabled: "{disabled}",
+                value: "{title}",
+                draggable: "true",
+
+                onmouseenter: move |_| {
+                    if state.read().clone() != ItemState::Selected {
+                        state.set(ItemState::Hovered);
+                        disabled.set(false);
+                    }
                 },
-                Key::Escape => {
-                    title.set(old_title.read().clone());
-                    state.set(ItemState::Normal);
-                    disabled.set(true);
+                onmouseleave: move |_| {
+                    if state.read().clone() != ItemState::Selected {
+                        state.set(ItemState::Normal);
+                        disabled.set(true);
+                    }
+                },
+                onclick: move |evt| {
+                    // Modifier clicks pick tasks for bulk actions instead of editing the title
+                    let modifiers = evt.modifiers();
+                    if modifiers.shift() || modifiers.ctrl() || modifiers.meta() {
+                        evt.prevent_default();
+                        on_select.call(if modifiers.shift() { SelectClick::Range } else { SelectClick::Toggle });
+                        return;
+                    }
+                    state.set(ItemState::Selected);
+                    old_title.set(title.read().clone());
                 },
-                Key::Delete => on_delete.call(task.id),
-                _ => {}
-            }},
-            ondragstart: move |_| {
-                *DRAGGING_ITEM.write() = Some(task.clone());
+                oninput: move |evt| title.set(evt.value()),
+                onblur: {
+                    let task = task.clone();
+                    move |_| {
+                        if title.read().clone().is_empty() {
+                            on_delete.call(task.id);
+                        } else if title.read().clone() != old_title.read().clone() {
+                            on_patch.call(TaskPatch { title: Some(title.read().clone()), ..TaskPatch::new(&task) });
+                        }
+                        state.set(ItemState::Normal);
+                        disabled.set(true);
+                    }
+                },
+                onkeydown: {
+                    let task = task.clone();
+                    move |evt| {
+                    // Leave Ctrl+Z to the input while the title is being edited
+                    if is_undo_shortcut(&evt) && state.read().clone() == ItemState::Selected {
+                        evt.stop_propagation();
+                        return;
+                    }
+                    match evt.key() {
+                    Key::Enter => {
+                        on_patch.call(TaskPatch { title: Some(title.read().clone()), ..TaskPatch::new(&task) });
+                        state.set(ItemState::Normal);
+                        disabled.set(true);
+                    },
+                    Key::Escape => {
+                        title.set(old_title.read().clone());
+                        state.set(ItemState::Normal);
+                        disabled.set(true);
+                    },
+                    Key::Delete => on_delete.call(task.id),
+                    _ => {}
+                }}},
+                ondragstart: {
+                    let task = task.clone();
+                    move |_| {
+                        *DRAGGING_ITEM.write() = Some(task.clone());
+                    }
+                }
+            }
+            if task.postponed_count > 0 {
+                span {
+                    class: "inbox-estimate",
+                    title: "Postponed {task.postponed_count} times",
+                    "↻{task.postponed_count}"
+                }
+            }
+            if let Some(minutes) = task.estimate_minutes {
+                span { class: "inbox-estimate", "{format_minutes(minutes)}" }
+            }
+            TagEditor { task_id: task.id }
+            if !members.is_empty() {
+                AssigneeSelect {
+                    task: task.clone(),
+                    members: members.clone(),
+                    on_patch: on_patch,
+                }
+            }
+            button {
+                class: "button",
+                "data-style": "ghost",
+                aria_label: "Details",
+                onclick: move |_| details_open.set(true),
+                "⋯"
+            }
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: details_open(),
+                on_open_change: move |v| details_open.set(v),
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if details_open() { "0" } else { "-1" },
+                        onclick: move |_| details_open.set(false),
+                        "×"
+                    }
+                    if details_open() {
+                        ItemManager { task: task.clone(), on_patch: on_patch }
+                    }
+                }
             }
         }
     }
 }
 
+/// Lets backlog members see and change who a task is assigned to.
 #[component]
-pub fn ItemManager() -> Element {
-    todo!()
-}
\ No newline at end of file
+fn AssigneeSelect(task: Task, members: Vec<Member>, on_patch: EventHandler<TaskPatch>) -> Element {
+    let selected = task.assignee_id.map(|id| id.0.to_string()).unwrap_or_default();
+
+    rsx! {
+        select {
+            class: "inbox-assignee",
+            aria_label: "Assignee",
+            onchange: move |evt| {
+                let assignee_id = members
+                    .iter()
+                    .find(|m| m.user.id.0.to_string() == evt.value())
+                    .map(|m| m.user.id);
+                on_patch.call(TaskPatch { assignee_id: Some(assignee_id), ..TaskPatch::new(&task) });
+            },
+            option {
+                value: "",
+                selected: selected.is_empty(),
+                "Unassigned"
+            }
+            for member in members.iter() {
+                option {
+                    key: "{member.user.id.0}",
+                    value: "{member.user.id.0}",
+                    selected: selected == member.user.id.0.to_string(),
+                    "{member.user.name}"
+                }
+            }
+        }
+    }
+}
+
+/// Detail view of a single task, with its change history.
+#[component]
+pub fn ItemManager(task: Task, on_patch: EventHandler<TaskPatch>) -> Element {
+    let mut history: Signal<Vec<TaskHistoryEntry>> = use_signal(|| vec![]);
+    let mut content = use_signal(|| task.content.clone().unwrap_or_default());
+    let mut estimate = use_signal(|| task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default());
+    let task_id = task.id;
+
+    let fetch_history_fn = move || {
+        spawn(async move {
+            match server::get_task_history(task_id).await {
+                Ok(fetched) => history.set(fetched),
+                Err(e) => eprintln!("Failed to fetch history of task {}: {}", task_id.0, e),
+            }
+        });
+    };
+
+    use_hook(|| {
+        fetch_history_fn();
+    });
+
+    let revert_fn = move |change_id: Id| {
+        spawn(async move {
+            match server::revert_task(change_id).await {
+                Ok(task) => {
+                    content.set(task.content.clone().unwrap_or_default());
+                    estimate.set(task.estimate_minutes.map(|m| m.to_string()).unwrap_or_default());
+                    *TASKS_VERSION.write() += 1;
+                    fetch_history_fn();
+                },
+                Err(e) => eprintln!("Failed to revert task {}: {}", task_id.0, e),
+            }
+        });
+    };
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "{task.title}"
+        }
+        DialogDescription {
+            textarea {
+                class: "inbox-input",
+                placeholder: "Notes",
+                value: "{content}",
+                oninput: move |evt| content.set(evt.value()),
+                onblur: {
+                    let task = task.clone();
+                    move |_| {
+                        let new_content = Some(content.read().clone()).filter(|c| !c.is_empty());
+                        if new_content != task.content {
+                            on_patch.call(TaskPatch { content: Some(new_content), ..TaskPatch::new(&task) });
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2 mt-2",
+                "Estimate (minutes)"
+                input {
+                    class: "inbox-input",
+                    r#type: "number",
+                    min: "0",
+                    step: "5",
+                    value: "{estimate}",
+                    oninput: move |evt| estimate.set(evt.value()),
+                    onblur: {
+                        let task = task.clone();
+                        move |_| {
+                            let new_estimate = estimate.read().trim().parse::<i32>().ok().filter(|m| *m > 0);
+                            if new_estimate != task.estimate_minutes {
+                                on_patch.call(TaskPatch { estimate_minutes: Some(new_estimate), ..TaskPatch::new(&task) });
+                            }
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2 mt-2",
+                "Project"
+                ProjectSelect {
+                    selected: task.project_id,
+                    on_change: {
+                        let task = task.clone();
+                        move |project_id| on_patch.call(TaskPatch { project_id: Some(project_id), ..TaskPatch::new(&task) })
+                    },
+                }
+            }
+            DependencyEditor { task: task.clone() }
+            h3 { class: "mt-4", "History" }
+            ol {
+                class: "task-history",
+                for entry in history.read().clone().iter().rev() {
+                    li {
+                        key: "{entry.change.id.0}",
+                        div {
+                            class: "flex items-center gap-2",
+                            span {
+                                class: "flex-1",
+                                "{describe_entry(entry)}"
+                            }
+                            button {
+                                class: "button",
+                                "data-style": "ghost",
+                                onclick: {
+                                    let change_id = entry.change.id;
+                                    move |_| revert_fn(change_id)
+                                },
+                                "Revert to this"
+                            }
+                        }
+                        ul {
+                            for change in entry.change.changes.0.iter() {
+                                li {
+                                    key: "{change.field}",
+                                    "{change.field}: {display_value(&change.before)} → {display_value(&change.after)}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn describe_entry(entry: &TaskHistoryEntry) -> String {
+    let actor = entry.actor.as_deref().unwrap_or("unknown");
+    let preferences = PREFERENCES.read();
+    let changed_at = preferences.to_local(entry.change.changed_at);
+    format!("{} {} · {} by {}", preferences.format_date(changed_at.date_naive()), changed_at.format("%H:%M"), entry.change.kind, actor)
+}
+
+fn display_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "—".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
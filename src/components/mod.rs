@@ -1,9 +1,30 @@
 mod inbox;
 mod schedule;
 mod backlog;
+mod bulk;
 mod calendar;
+mod capacity;
+mod dependency;
 mod item;
+mod preferences;
+mod project;
+mod range;
+mod saved_view;
+mod search;
+mod session;
+mod smart_list;
+mod tag;
+mod timeline;
+mod undo;
 
 pub use inbox::*;
 pub use schedule::*;
-pub use backlog::*;
\ No newline at end of file
+pub use backlog::*;
+pub use smart_list::*;
+pub use saved_view::*;
+pub use search::*;
+pub use project::{ProjectsPage, refresh_projects};
+pub use tag::refresh_tags;
+pub use preferences::refresh_preferences;
+pub use session::{SIGNED_IN, SignIn, refresh_session, sign_out};
+pub use undo::{UndoToast, handle_undo_shortcut};
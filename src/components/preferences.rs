@@ -0,0 +1,157 @@
+use dioxus::prelude::*;
+use chrono::{NaiveDate, Weekday};
+use chrono_tz::{TZ_VARIANTS, Tz};
+use crate::backend::server;
+use crate::backend::preferences::{DateFormat, Locale, Preferences, WEEK_STARTS};
+use crate::backend::validation::error_message;
+use dioxus_primitives::dialog::{DialogDescription, DialogTitle};
+
+/// The current user's preferences, with the defaults until they are fetched.
+pub static PREFERENCES: GlobalSignal<Preferences> = Signal::global(Preferences::default);
+
+pub fn refresh_preferences() {
+    spawn(async move {
+        match server::get_preferences().await {
+            Ok(fetched) => *PREFERENCES.write() = fetched,
+            Err(e) => eprintln!("Failed to fetch preferences: {}", e),
+        }
+    });
+}
+
+/// Settings for the week start, the language of day and month names, the date format and the
+/// time zone.
+#[component]
+pub fn PreferencesEditor() -> Element {
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let save_fn = move |preferences: Preferences| {
+        spawn(async move {
+            match server::set_preferences(preferences).await {
+                Ok(saved) => {
+                    error.set(None);
+                    *PREFERENCES.write() = saved;
+                },
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+
+    let preferences = PREFERENCES.read().clone();
+    let example = preferences.format_day(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap_or_default());
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Preferences"
+        }
+        DialogDescription {
+            label {
+                class: "flex items-center gap-2",
+                span { class: "w-32", "Week starts on" }
+                select {
+                    class: "inbox-assignee",
+                    onchange: {
+                        let preferences = preferences.clone();
+                        move |evt: Event<FormData>| {
+                            if let Ok(week_start) = evt.value().parse::<Weekday>() {
+                                save_fn(Preferences { week_start, ..preferences.clone() });
+                            }
+                        }
+                    },
+                    for weekday in WEEK_STARTS {
+                        option {
+                            value: "{weekday}",
+                            selected: preferences.week_start == weekday,
+                            "{preferences.locale.weekday_name(weekday)}"
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2",
+                span { class: "w-32", "Language" }
+                select {
+                    class: "inbox-assignee",
+                    onchange: {
+                        let preferences = preferences.clone();
+                        move |evt: Event<FormData>| {
+                            if let Ok(locale) = evt.value().parse::<Locale>() {
+                                save_fn(Preferences { locale, ..preferences.clone() });
+                            }
+                        }
+                    },
+                    for locale in Locale::ALL {
+                        option {
+                            value: "{locale.label()}",
+                            selected: preferences.locale == locale,
+                            "{locale.label()}"
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2",
+                span { class: "w-32", "Date format" }
+                select {
+                    class: "inbox-assignee",
+                    onchange: {
+                        let preferences = preferences.clone();
+                        move |evt: Event<FormData>| {
+                            if let Ok(date_format) = evt.value().parse::<DateFormat>() {
+                                save_fn(Preferences { date_format, ..preferences.clone() });
+                            }
+                        }
+                    },
+                    for format in DateFormat::ALL {
+                        option {
+                            value: "{format.label()}",
+                            selected: preferences.date_format == format,
+                            "{format.label()}"
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2",
+                span { class: "w-32", "Time zone" }
+                select {
+                    class: "inbox-assignee",
+                    onchange: {
+                        let preferences = preferences.clone();
+                        move |evt: Event<FormData>| {
+                            if let Ok(time_zone) = evt.value().parse::<Tz>() {
+                                save_fn(Preferences { time_zone, ..preferences.clone() });
+                            }
+                        }
+                    },
+                    for time_zone in TZ_VARIANTS {
+                        option {
+                            value: "{time_zone.name()}",
+                            selected: preferences.time_zone == time_zone,
+                            "{time_zone.name()}"
+                        }
+                    }
+                }
+            }
+            label {
+                class: "flex items-center gap-2",
+                input {
+                    r#type: "checkbox",
+                    checked: preferences.show_week_numbers,
+                    onchange: {
+                        let preferences = preferences.clone();
+                        move |evt: Event<FormData>| {
+                            save_fn(Preferences { show_week_numbers: evt.checked(), ..preferences.clone() });
+                        }
+                    },
+                }
+                "Show week numbers"
+            }
+            div { class: "mt-4", "Dates look like: {example}" }
+            div { "Today is {preferences.format_day(preferences.today())}" }
+            if let Some(message) = error() {
+                div { class: "query-error", "{message}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+use dioxus::prelude::*;
+use chrono::NaiveDate;
+use crate::backend::server;
+use crate::backend::model::{BurndownPoint, Id, Milestone, Project, ProjectOverview, ProjectStatus};
+use super::undo::TASKS_VERSION;
+
+/// All projects, for the project pickers on backlogs and tasks.
+pub static PROJECTS: GlobalSignal<Vec<Project>> = Signal::global(|| vec![]);
+
+const BURNDOWN_WIDTH: usize = 400;
+const BURNDOWN_HEIGHT: usize = 120;
+
+pub fn refresh_projects() {
+    spawn(async move {
+        match server::get_projects().await {
+            Ok(fetched) => *PROJECTS.write() = fetched,
+            Err(e) => eprintln!("Failed to fetch projects: {}", e),
+        }
+    });
+}
+
+fn parse_date_input(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+/// Picks the project something belongs to, or none.
+#[component]
+pub fn ProjectSelect(selected: Option<Id>, on_change: EventHandler<Option<Id>>) -> Element {
+    rsx! {
+        select {
+            class: "inbox-assignee",
+            aria_label: "Project",
+            onchange: move |evt| {
+                let project = PROJECTS.read().iter().find(|p| p.id.0.to_string() == evt.value()).map(|p| p.id);
+                on_change.call(project);
+            },
+            option { value: "", selected: selected.is_none(), "No project" }
+            for project in PROJECTS.read().clone() {
+                option {
+                    key: "{project.id.0}",
+                    value: "{project.id.0}",
+                    selected: selected == Some(project.id),
+                    "{project.name}"
+                }
+            }
+        }
+    }
+}
+
+/// Lists projects and shows the overview of the selected one.
+#[component]
+pub fn ProjectsPage() -> Element {
+    let mut selected: Signal<Option<Id>> = use_signal(|| None);
+    let mut new_project = use_signal(|| String::new());
+
+    let create_project_fn = move || {
+        let name = new_project.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            match server::create_project(name).await {
+                Ok(project) => {
+                    selected.set(Some(project.id));
+                    PROJECTS.write().push(project);
+                    new_project.set(String::new());
+                },
+                Err(e) => eprintln!("Failed to create project: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        document::Stylesheet { href: asset!("/assets/inbox.css") }
+        document::Stylesheet { href: asset!("/assets/button.css") }
+        document::Stylesheet { href: asset!("/assets/project.css") }
+        div {
+            class: "grid grid-cols-4 gap-4 p-4",
+            div {
+                class: "col-span-1 border border-gray-400 bg-gray-100 p-4 flex flex-col gap-2",
+                "Projects"
+                for project in PROJECTS.read().clone() {
+                    button {
+                        key: "{project.id.0}",
+                        class: "button",
+                        "data-style": if selected() == Some(project.id) { "primary" } else { "ghost" },
+                        onclick: move |_| selected.set(Some(project.id)),
+                        "{project.name}"
+                    }
+                }
+                div {
+                    class: "flex",
+                    input {
+                        class: "inbox-input flex-1",
+                        r#type: "text",
+                        placeholder: "Project name",
+                        value: "{new_project}",
+                        oninput: move |evt| new_project.set(evt.value()),
+                        onkeydown: move |evt| match evt.key() {
+                            Key::Enter => create_project_fn(),
+                            Key::Escape => new_project.set(String::new()),
+                            _ => {}
+                        }
+                    }
+                    button {
+                        class: "inbox-button",
+                        onclick: move |_| create_project_fn(), "Add"
+                    }
+                }
+            }
+            div {
+                class: "col-span-3 border border-gray-400 bg-gray-100 p-4",
+                if let Some(project_id) = selected() {
+                    ProjectOverviewPanel {
+                        key: "{project_id.0}",
+                        project_id: project_id,
+                        on_deleted: move |_| selected.set(None),
+                    }
+                } else {
+                    "Select or create a project"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ProjectOverviewPanel(project_id: Id, on_deleted: EventHandler<()>) -> Element {
+    let mut overview: Signal<Option<ProjectOverview>> = use_signal(|| None);
+    let mut new_milestone = use_signal(|| String::new());
+    let mut new_milestone_due = use_signal(|| String::new());
+
+    let fetch_fn = move || {
+        spawn(async move {
+            match server::get_project_overview(project_id).await {
+                Ok(fetched) => overview.set(Some(fetched)),
+                Err(e) => eprintln!("Failed to fetch project overview: {}", e),
+            }
+        });
+    };
+
+    // Refetch when tasks change, so progress stays current
+    use_effect(move || {
+        let _ = TASKS_VERSION.read();
+        fetch_fn();
+    });
+
+    let update_project_fn = move |project: Project| {
+        spawn(async move {
+            match server::update_project(project).await {
+                Ok(updated) => {
+                    if let Some(p) = PROJECTS.write().iter_mut().find(|p| p.id == updated.id) {
+                        *p = updated;
+                    }
+                    fetch_fn();
+                },
+                Err(e) => eprintln!("Failed to update project: {}", e),
+            }
+        });
+    };
+
+    let delete_project_fn = move || {
+        spawn(async move {
+            match server::delete_project(project_id).await {
+                Ok(_) => {
+                    PROJECTS.write().retain(|p| p.id != project_id);
+                    on_deleted.call(());
+                },
+                Err(e) => eprintln!("Failed to delete project: {}", e),
+            }
+        });
+    };
+
+    let add_milestone_fn = move || {
+        let name = new_milestone.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let due = parse_date_input(&new_milestone_due.read());
+        spawn(async move {
+            match server::create_milestone(project_id, name, due).await {
+                Ok(_) => {
+                    new_milestone.set(String::new());
+                    new_milestone_due.set(String::new());
+                    fetch_fn();
+                },
+                Err(e) => eprintln!("Failed to create milestone: {}", e),
+            }
+        });
+    };
+
+    let update_milestone_fn = move |milestone: Milestone| {
+        spawn(async move {
+            match server::update_milestone(milestone).await {
+                Ok(_) => fetch_fn(),
+                Err(e) => eprintln!("Failed to update milestone: {}", e),
+            }
+        });
+    };
+
+    let delete_milestone_fn = move |milestone_id: Id| {
+        spawn(async move {
+            match server::delete_milestone(milestone_id).await {
+                Ok(_) => fetch_fn(),
+                Err(e) => eprintln!("Failed to delete milestone: {}", e),
+            }
+        });
+    };
+
+    // Swaps a milestone with its neighbour above (-1) or below (+1)
+    let move_milestone_fn = move |index: usize, offset: isize| {
+        let Some(current) = overview.read().clone() else {
+            return;
+        };
+        let mut ids: Vec<Id> = current.milestones.iter().map(|m| m.id).collect();
+        let Some(other) = index.checked_add_signed(offset).filter(|i| *i < ids.len()) else {
+            return;
+        };
+        ids.swap(index, other);
+        spawn(async move {
+            match server::reorder_milestones(project_id, ids).await {
+                Ok(_) => fetch_fn(),
+                Err(e) => eprintln!("Failed to reorder milestones: {}", e),
+            }
+        });
+    };
+
+    let Some(current) = overview.read().clone() else {
+        return rsx! { "Loading…" };
+    };
+    let project = current.project.clone();
+    let percent = if current.total_tasks > 0 { current.completed_tasks * 100 / current.total_tasks } else { 0 };
+    let target = project.target_date.map(|d| d.to_string()).unwrap_or_default();
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 text-left",
+            input {
+                class: "inbox-input text-lg",
+                r#type: "text",
+                value: "{project.name}",
+                onchange: {
+                    let project = project.clone();
+                    move |evt: FormEvent| update_project_fn(Project { name: evt.value(), ..project.clone() })
+                },
+            }
+            textarea {
+                class: "inbox-input",
+                placeholder: "Description",
+                value: "{project.description.clone().unwrap_or_default()}",
+                onchange: {
+                    let project = project.clone();
+                    move |evt: FormEvent| {
+                        let description = Some(evt.value()).filter(|d| !d.trim().is_empty());
+                        update_project_fn(Project { description, ..project.clone() })
+                    }
+                },
+            }
+            div {
+                class: "flex items-center gap-2",
+                select {
+                    class: "inbox-assignee",
+                    aria_label: "Status",
+                    onchange: {
+                        let project = project.clone();
+                        move |evt: FormEvent| {
+                            if let Ok(status) = evt.value().parse() {
+                                update_project_fn(Project { status, ..project.clone() });
+                            }
+                        }
+                    },
+                    for status in ProjectStatus::ALL {
+                        option {
+                            value: "{status}",
+                            selected: project.status == status,
+                            "{status}"
+                        }
+                    }
+                }
+                label {
+                    "Target date "
+                    input {
+                        r#type: "date",
+                        value: "{target}",
+                        onchange: {
+                            let project = project.clone();
+                            move |evt: FormEvent| update_project_fn(Project { target_date: parse_date_input(&evt.value()), ..project.clone() })
+                        },
+                    }
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| delete_project_fn(),
+                    "Delete project"
+                }
+            }
+
+            h3 { class: "mt-4", "Progress" }
+            div {
+                class: "project-progress",
+                div { class: "project-progress-bar", style: "width: {percent}%;" }
+            }
+            "{current.completed_tasks} of {current.total_tasks} tasks completed ({percent}%)"
+            Burndown { points: current.burndown.clone() }
+
+            h3 { class: "mt-4", "Milestones" }
+            for (index, milestone) in current.milestones.iter().cloned().enumerate() {
+                div {
+                    key: "{milestone.id.0}",
+                    class: "flex items-center gap-2",
+                    input {
+                        r#type: "checkbox",
+                        checked: milestone.completed,
+                        onchange: {
+                            let milestone = milestone.clone();
+                            move |evt: FormEvent| update_milestone_fn(Milestone { completed: evt.checked(), ..milestone.clone() })
+                        },
+                    }
+                    span {
+                        class: if milestone.completed { "flex-1 line-through" } else { "flex-1" },
+                        "{milestone.name}"
+                    }
+                    if let Some(due) = milestone.due_date {
+                        span { class: "inbox-estimate", "{due}" }
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move up",
+                        onclick: move |_| move_milestone_fn(index, -1),
+                        "↑"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        aria_label: "Move down",
+                        onclick: move |_| move_milestone_fn(index, 1),
+                        "↓"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| delete_milestone_fn(milestone.id),
+                        "Remove"
+                    }
+                }
+            }
+            div {
+                class: "flex gap-2",
+                input {
+                    class: "inbox-input flex-1",
+                    r#type: "text",
+                    placeholder: "Milestone",
+                    value: "{new_milestone}",
+                    oninput: move |evt| new_milestone.set(evt.value()),
+                    onkeydown: move |evt| {
+                        if evt.key() == Key::Enter {
+                            add_milestone_fn();
+                        }
+                    }
+                }
+                input {
+                    r#type: "date",
+                    value: "{new_milestone_due}",
+                    oninput: move |evt| new_milestone_due.set(evt.value()),
+                }
+                button {
+                    class: "inbox-button",
+                    onclick: move |_| add_milestone_fn(), "Add"
+                }
+            }
+        }
+    }
+}
+
+/// Open tasks per day as a line chart.
+#[component]
+fn Burndown(points: Vec<BurndownPoint>) -> Element {
+    if points.len() < 2 {
+        return rsx! {};
+    }
+    let max = points.iter().map(|p| p.remaining).max().unwrap_or(0).max(1) as usize;
+    let step = BURNDOWN_WIDTH as f64 / (points.len() - 1) as f64;
+    let line = points
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{:.1},{}", i as f64 * step, BURNDOWN_HEIGHT - p.remaining as usize * BURNDOWN_HEIGHT / max))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let first = points[0].date;
+    let last = points[points.len() - 1].date;
+
+    rsx! {
+        svg {
+            class: "project-burndown",
+            view_box: "0 0 {BURNDOWN_WIDTH} {BURNDOWN_HEIGHT}",
+            width: "{BURNDOWN_WIDTH}",
+            height: "{BURNDOWN_HEIGHT}",
+            polyline {
+                points: "{line}",
+                fill: "none",
+                stroke: "currentColor",
+                stroke_width: "2",
+            }
+        }
+        div {
+            class: "flex justify-between inbox-estimate",
+            style: "width: {BURNDOWN_WIDTH}px;",
+            span { "{first}" }
+            span { "{max} open at most" }
+            span { "{last}" }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use dioxus::prelude::*;
+use std::collections::BTreeMap;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use crate::backend::server;
+use crate::backend::model::{Id, Task, TaskPatch};
+use crate::backend::validation::{RANGE_DAYS_MAX, error_message, is_conflict};
+use super::item::{DRAGGING_ITEM, PreloadedTasks};
+use super::undo::{TASKS_VERSION, show_undo_toast};
+use super::tag::{TagDots, load_task_tags};
+use super::dependency::rescheduled_message;
+use super::preferences::PREFERENCES;
+
+/// Days the agenda shows at first, and adds each time more are asked for.
+const AGENDA_DAYS: u64 = 30;
+
+/// The tasks of a range of days, grouped by day.
+#[derive(Clone, PartialEq, Default)]
+pub(super) struct RangeTasks {
+    by_day: BTreeMap<NaiveDate, Vec<Task>>,
+    blocked: Vec<Id>,
+}
+
+impl RangeTasks {
+    pub fn on(&self, day: NaiveDate) -> Vec<Task> {
+        self.by_day.get(&day).cloned().unwrap_or_default()
+    }
+
+    /// What the list of `day` shows, for handing to its `ItemList`.
+    pub fn preloaded(&self, day: NaiveDate) -> PreloadedTasks {
+        let tasks = self.on(day);
+        let blocked = self.blocked.iter().filter(|id| tasks.iter().any(|t| t.id == **id)).copied().collect();
+        PreloadedTasks { tasks, blocked }
+    }
+}
+
+/// Tasks scheduled from `start` to `end`, with their tags and which are blocked, fetched for the
+/// whole range at once and again whenever tasks change.
+pub(super) fn use_tasks_in_range(start: NaiveDate, end: NaiveDate) -> Signal<RangeTasks> {
+    let mut range: Signal<RangeTasks> = use_signal(RangeTasks::default);
+
+    use_effect(use_reactive((&start, &end), move |(start, end)| {
+        let _ = TASKS_VERSION.read();
+        spawn(async move {
+            let by_day = match server::get_tasks_in_range(start, end).await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    eprintln!("Failed to fetch tasks: {}", e);
+                    return;
+                },
+            };
+            let ids: Vec<Id> = by_day.values().flatten().map(|t| t.id).collect();
+            load_task_tags(ids.clone());
+            let blocked = match server::get_blocked_tasks(ids).await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    eprintln!("Failed to fetch blocked tasks: {}", e);
+                    vec![]
+                },
+            };
+            range.set(RangeTasks { by_day, blocked });
+        });
+    }));
+
+    range
+}
+
+/// Moves the task being dragged onto `day`.
+fn reschedule_dragged(day: NaiveDate) {
+    let Some(task) = DRAGGING_ITEM.read().clone() else {
+        return;
+    };
+    if task.scheduled_date == Some(day) {
+        return;
+    }
+    let patch = TaskPatch { scheduled_date: Some(Some(day)), ..TaskPatch::new(&task) };
+    spawn(async move {
+        match server::patch_task(patch).await {
+            Ok(moved) => {
+                *TASKS_VERSION.write() += 1;
+                show_undo_toast(rescheduled_message(moved.id, "Task rescheduled").await);
+            },
+            Err(e) if is_conflict(&e) => {
+                show_undo_toast(error_message(&e));
+                *TASKS_VERSION.write() += 1;
+            },
+            Err(e) => eprintln!("Failed to reschedule task: {}", e),
+        }
+    });
+}
+
+/// A task in a calendar cell or agenda day, which can be dragged to another day or list.
+#[component]
+fn RangeTask(task: Task) -> Element {
+    rsx! {
+        div {
+            class: if task.completed { "range-task completed" } else { "range-task" },
+            draggable: "true",
+            ondragstart: {
+                let task = task.clone();
+                move |_| *DRAGGING_ITEM.write() = Some(task.clone())
+            },
+            TagDots { task_id: task.id }
+            "{task.title}"
+        }
+    }
+}
+
+/// Weeks of the month of `selected_date`, with the tasks of each day in its cell.
+#[component]
+pub fn MonthView(selected_date: NaiveDate) -> Element {
+    let first = selected_date.with_day(1).unwrap_or(selected_date);
+    let last = first.checked_add_months(Months::new(1)).and_then(|d| d.pred_opt()).unwrap_or(first);
+    let preferences = PREFERENCES.read().clone();
+    let start = preferences.week_of(first);
+    let end = preferences.week_of(last).checked_add_days(Days::new(6)).unwrap_or(last);
+    let tasks = use_tasks_in_range(start, end);
+    let today = preferences.today();
+
+    let days: Vec<NaiveDate> = start.iter_days().take_while(|d| *d <= end).collect();
+
+    rsx! {
+        div {
+            class: "month-grid",
+            "data-week-numbers": preferences.show_week_numbers,
+            if preferences.show_week_numbers {
+                div { class: "month-weekday" }
+            }
+            for weekday in preferences.weekdays() {
+                div { key: "{weekday}", class: "month-weekday", "{preferences.locale.weekday_short(weekday)}" }
+            }
+            for day in days {
+                if preferences.show_week_numbers && day.weekday() == preferences.week_start {
+                    div { key: "week-{day}", class: "month-week-number", "{day.iso_week().week()}" }
+                }
+                div {
+                    key: "{day}",
+                    class: "month-day",
+                    "data-month": if day.month() == first.month() { "current" } else { "other" },
+                    "data-today": day == today,
+                    ondragover: move |e| e.prevent_default(),
+                    ondrop: move |_| reschedule_dragged(day),
+                    div { class: "month-day-number", "{day.day()}" }
+                    for task in tasks.read().on(day) {
+                        RangeTask { key: "{task.id.0}", task: task }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The days from `selected_date` on as one scrolling list, with more days loaded on request.
+#[component]
+pub fn AgendaView(selected_date: NaiveDate) -> Element {
+    let mut days_shown = use_signal(|| AGENDA_DAYS);
+    let end = selected_date.checked_add_days(Days::new(days_shown() - 1)).unwrap_or(selected_date);
+    let tasks = use_tasks_in_range(selected_date, end);
+
+    let days: Vec<NaiveDate> = selected_date.iter_days().take_while(|d| *d <= end).collect();
+
+    rsx! {
+        div {
+            class: "agenda",
+            for day in days {
+                div {
+                    key: "{day}",
+                    class: "agenda-day",
+                    ondragover: move |e| e.prevent_default(),
+                    ondrop: move |_| reschedule_dragged(day),
+                    div { class: "agenda-date", "{PREFERENCES.read().format_day(day)}" }
+                    for task in tasks.read().on(day) {
+                        RangeTask { key: "{task.id.0}", task: task }
+                    }
+                }
+            }
+            if days_shown() + AGENDA_DAYS <= RANGE_DAYS_MAX as u64 {
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| days_shown += AGENDA_DAYS,
+                    "Show {AGENDA_DAYS} more days"
+                }
+            }
+        }
+    }
+}
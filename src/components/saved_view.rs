@@ -0,0 +1,321 @@
+use dioxus::prelude::*;
+use chrono::NaiveDate;
+use super::item::ItemList;
+use super::tag::TAGS;
+use crate::backend::server;
+use crate::backend::model::{BacklogFilter, DateFilter, Id, Role, SavedView, TaskFilter, TaskSort};
+use crate::backend::query::parse_query;
+use dioxus_primitives::dialog::{DialogContent, DialogDescription, DialogRoot, DialogTitle};
+
+/// The user's saved views as tabs, each showing the tasks currently matching it.
+#[component]
+pub fn SavedViewsApp() -> Element {
+    let mut views: Signal<Vec<SavedView>> = use_signal(|| vec![]);
+    let mut selected: Signal<Option<Id>> = use_signal(|| None);
+    let mut editing: Signal<Option<Option<SavedView>>> = use_signal(|| None);
+    let mut roles: Signal<Vec<Role>> = use_signal(|| vec![]);
+    let today = PREFERENCES.read().today();
+
+    use_hook(|| {
+        spawn(async move {
+            match server::get_roles().await {
+                Ok(fetched) => roles.set(fetched),
+                Err(e) => eprintln!("Failed to fetch roles: {}", e),
+            }
+            match server::get_saved_views().await {
+                Ok(fetched) => {
+                    selected.set(fetched.first().map(|v| v.id));
+                    views.set(fetched);
+                },
+                Err(e) => eprintln!("Failed to fetch saved views: {}", e),
+            }
+        });
+    });
+
+    let delete_view_fn = move |view_id: Id| {
+        spawn(async move {
+            match server::delete_saved_view(view_id).await {
+                Ok(_) => {
+                    views.write().retain(|v| v.id != view_id);
+                    selected.set(views.read().first().map(|v| v.id));
+                },
+                Err(e) => eprintln!("Failed to delete saved view: {}", e),
+            }
+        });
+    };
+
+    let on_saved = move |view: SavedView| {
+        let mut list = views.write();
+        match list.iter_mut().find(|v| v.id == view.id) {
+            Some(existing) => *existing = view.clone(),
+            None => list.push(view.clone()),
+        }
+        selected.set(Some(view.id));
+        editing.set(None);
+    };
+
+    let current = views.read().iter().find(|v| Some(v.id) == selected()).cloned();
+
+    rsx! {
+        div {
+            class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
+            "Views",
+            div {
+                class: "flex flex-wrap gap-1 justify-center my-2",
+                for view in views.read().clone() {
+                    button {
+                        key: "{view.id.0}",
+                        class: "button",
+                        "data-style": if selected() == Some(view.id) { "primary" } else { "ghost" },
+                        onclick: move |_| selected.set(Some(view.id)),
+                        "{view.name}"
+                    }
+                }
+                button {
+                    class: "button",
+                    "data-style": "outline",
+                    onclick: move |_| editing.set(Some(None)),
+                    "New view"
+                }
+            }
+            if let Some(view) = current {
+                div {
+                    class: "flex justify-center gap-2 mb-2",
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: {
+                            let view = view.clone();
+                            move |_| editing.set(Some(Some(view.clone())))
+                        },
+                        "Edit"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| delete_view_fn(view.id),
+                        "Delete"
+                    }
+                }
+                // Keyed on the whole filter so the list refetches after the view is edited
+                ItemList {
+                    key: "{view.id.0}-{view_filter(&view, today, &roles.read()):?}",
+                    filter: view_filter(&view, today, &roles.read()),
+                }
+            }
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: editing.read().is_some(),
+                on_open_change: move |open: bool| if !open { editing.set(None) },
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if editing.read().is_some() { "0" } else { "-1" },
+                        onclick: move |_| editing.set(None),
+                        "×"
+                    }
+                    if let Some(view) = editing() {
+                        SavedViewEditor {
+                            view: view,
+                            on_saved: on_saved,
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The filter a view currently stands for, from its query when it has one.
+fn view_filter(view: &SavedView, today: NaiveDate, roles: &[Role]) -> TaskFilter {
+    match &view.query {
+        Some(query) => parse_query(query, today, roles, &TAGS.read()).unwrap_or_else(|_| view.filter_on(today)),
+        None => view.filter_on(today),
+    }
+}
+
+/// Form for creating a view, or editing `view` when one is given.
+#[component]
+fn SavedViewEditor(view: Option<SavedView>, on_saved: EventHandler<SavedView>) -> Element {
+    let initial = view.clone().map(|v| v.filter).unwrap_or(TaskFilter {
+        scheduled: DateFilter::Any,
+        backlog: BacklogFilter::Any,
+        completed: Some(false),
+        ..Default::default()
+    });
+    let mut name = use_signal(|| view.as_ref().map(|v| v.name.clone()).unwrap_or_default());
+    let mut upcoming_days = use_signal(|| view.as_ref().and_then(|v| v.upcoming_days));
+    let mut role_id = use_signal(|| initial.role_id);
+    let mut important = use_signal(|| initial.important == Some(true));
+    let mut urgent = use_signal(|| initial.urgent == Some(true));
+    let mut include_completed = use_signal(|| initial.completed.is_none());
+    let mut text = use_signal(|| initial.text.clone().unwrap_or_default());
+    let mut sort = use_signal(|| initial.sort);
+    let mut query = use_signal(|| view.as_ref().and_then(|v| v.query.clone()).unwrap_or_default());
+    let mut query_error: Signal<Option<String>> = use_signal(|| None);
+    let mut roles: Signal<Vec<Role>> = use_signal(|| vec![]);
+
+    use_hook(|| {
+        spawn(async move {
+            match server::get_roles().await {
+                Ok(fetched) => roles.set(fetched),
+                Err(e) => eprintln!("Failed to fetch roles: {}", e),
+            }
+        });
+    });
+
+    let is_new = view.is_none();
+    let mut save_fn = move || {
+        let view_name = name.read().trim().to_string();
+        if view_name.is_empty() {
+            return;
+        }
+        // A query takes the place of the fields below it
+        let view_query = Some(query.read().trim().to_string()).filter(|q| !q.is_empty());
+        let parsed = match &view_query {
+            Some(q) => match parse_query(q, PREFERENCES.read().today(), &roles.read(), &TAGS.read()) {
+                Ok(parsed) => Some(parsed),
+                Err(e) => {
+                    query_error.set(Some(e.to_string()));
+                    return;
+                },
+            },
+            None => None,
+        };
+        query_error.set(None);
+        let filter = parsed.unwrap_or(TaskFilter {
+            role_id: role_id(),
+            important: important().then_some(true),
+            urgent: urgent().then_some(true),
+            completed: if include_completed() { None } else { Some(false) },
+            text: Some(text.read().trim().to_string()).filter(|t| !t.is_empty()),
+            sort: sort(),
+            ..initial.clone()
+        });
+        let existing = view.clone();
+        spawn(async move {
+            let saved = match existing {
+                Some(existing) => server::update_saved_view(SavedView {
+                    name: view_name,
+                    filter,
+                    upcoming_days: upcoming_days(),
+                    query: view_query,
+                    ..existing
+                }).await,
+                None => server::create_saved_view(view_name, filter, upcoming_days(), view_query).await,
+            };
+            match saved {
+                Ok(saved) => on_saved.call(saved),
+                Err(e) => eprintln!("Failed to save view: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            if is_new { "New view" } else { "Edit view" }
+        }
+        DialogDescription {
+            class: "flex flex-col gap-2",
+            input {
+                class: "inbox-input",
+                r#type: "text",
+                placeholder: "View name",
+                value: "{name}",
+                oninput: move |evt| name.set(evt.value()),
+            }
+            input {
+                class: "inbox-input",
+                r#type: "text",
+                placeholder: "Query, e.g. role:work is:open due<+14d",
+                value: "{query}",
+                oninput: move |evt| query.set(evt.value()),
+            }
+            if let Some(message) = query_error() {
+                div { class: "query-error", "{message}" }
+            }
+            select {
+                class: "inbox-assignee",
+                aria_label: "Role",
+                onchange: move |evt| role_id.set(roles.read().iter().find(|r| r.id.0.to_string() == evt.value()).map(|r| r.id)),
+                option { value: "", selected: role_id().is_none(), "Any role" }
+                for role in roles.read().clone() {
+                    option {
+                        key: "{role.id.0}",
+                        value: "{role.id.0}",
+                        selected: role_id() == Some(role.id),
+                        "{role.name}"
+                    }
+                }
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: important(),
+                    onchange: move |evt| important.set(evt.checked()),
+                }
+                " Important"
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: urgent(),
+                    onchange: move |evt| urgent.set(evt.checked()),
+                }
+                " Urgent"
+            }
+            label {
+                input {
+                    r#type: "checkbox",
+                    checked: include_completed(),
+                    onchange: move |evt| include_completed.set(evt.checked()),
+                }
+                " Include completed"
+            }
+            label {
+                class: "flex items-center gap-2",
+                "Next"
+                input {
+                    class: "inbox-input w-20",
+                    r#type: "number",
+                    min: "1",
+                    placeholder: "any",
+                    value: "{upcoming_days().map(|d| d.to_string()).unwrap_or_default()}",
+                    onchange: move |evt| upcoming_days.set(evt.value().trim().parse().ok().filter(|d: &i32| *d > 0)),
+                }
+                "days"
+            }
+            input {
+                class: "inbox-input",
+                r#type: "text",
+                placeholder: "Containing text",
+                value: "{text}",
+                oninput: move |evt| text.set(evt.value()),
+            }
+            select {
+                class: "inbox-assignee",
+                aria_label: "Sort by",
+                onchange: move |evt| {
+                    if let Some(choice) = TaskSort::ALL.into_iter().find(|s| s.label() == evt.value()) {
+                        sort.set(choice);
+                    }
+                },
+                for choice in TaskSort::ALL {
+                    option {
+                        value: "{choice.label()}",
+                        selected: sort() == choice,
+                        "Sort by {choice.label()}"
+                    }
+                }
+            }
+            button {
+                class: "inbox-button",
+                onclick: move |_| save_fn(),
+                "Save"
+            }
+        }
+    }
+}
@@ -1,14 +1,84 @@
 use dioxus::prelude::*;
 
-use chrono::{Datelike, Utc, NaiveDate, Weekday, Days};
+use chrono::{Datelike, NaiveDate, Days};
 use super::item::ItemList;
-use crate::backend::model::TaskFilter;
+use crate::backend::server;
+use crate::backend::model::{Capacity, Task, TaskFilter, capacity_on, planned_minutes};
 use super::calendar::DatePicker;
+use super::capacity::{CapacityIndicator, CapacityManager, RebalancePanel};
+use super::timeline::TimelineView;
+use super::range::{AgendaView, MonthView, use_tasks_in_range};
+use super::item::PreloadedTasks;
+use super::preferences::{PREFERENCES, PreferencesEditor};
+use super::undo::TASKS_VERSION;
+use dioxus_primitives::dialog::{DialogContent, DialogRoot};
+
+#[derive(PartialEq, Clone, Copy)]
+enum ScheduleView {
+    Day,
+    Week,
+    Month,
+    Agenda,
+    DayTimeline,
+    WeekTimeline,
+}
+
+impl ScheduleView {
+    const ALL: [ScheduleView; 6] = [
+        ScheduleView::Day,
+        ScheduleView::Week,
+        ScheduleView::Month,
+        ScheduleView::Agenda,
+        ScheduleView::DayTimeline,
+        ScheduleView::WeekTimeline,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ScheduleView::Day => "Day",
+            ScheduleView::Week => "Week",
+            ScheduleView::Month => "Month",
+            ScheduleView::Agenda => "Agenda",
+            ScheduleView::DayTimeline => "Day timeline",
+            ScheduleView::WeekTimeline => "Week timeline",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum ScheduleDialog {
+    Capacity,
+    Rebalance,
+    Preferences,
+}
 
 #[component]
 pub fn ScheduleApp() -> Element {
-    let mut selected_date: Signal<NaiveDate> = use_signal(|| Utc::now().naive_local().date());
+    let mut selected_date: Signal<NaiveDate> = use_signal(|| PREFERENCES.read().today());
     let week_nr = use_memo(move || selected_date.read().iso_week().week());
+    let mut capacities: Signal<Vec<Capacity>> = use_signal(|| vec![]);
+    let mut dialog: Signal<Option<ScheduleDialog>> = use_signal(|| None);
+    let mut view = use_signal(|| ScheduleView::Week);
+    let week_start = use_memo(move || PREFERENCES.read().week_of(selected_date()));
+    let week_days = use_memo(move || {
+        (0..7).filter_map(|i| week_start().checked_add_days(Days::new(i))).collect::<Vec<_>>()
+    });
+    let time_zone = use_memo(move || PREFERENCES.read().time_zone);
+
+    // Today may be another day once the user's time zone is known
+    use_effect(move || {
+        let _ = time_zone();
+        selected_date.set(PREFERENCES.peek().today());
+    });
+
+    use_hook(|| {
+        spawn(async move {
+            match server::get_capacities().await {
+                Ok(fetched) => capacities.set(fetched),
+                Err(e) => eprintln!("Failed to fetch capacities: {}", e),
+            }
+        });
+    });
 
     rsx! {
         document::Stylesheet { href: asset!("/assets/calendar.css") }
@@ -18,43 +88,189 @@ pub fn ScheduleApp() -> Element {
 
             div {
                 class: "w-fit", // prevents stretching
-                "Week {week_nr}",
+                if PREFERENCES.read().show_week_numbers {
+                    "Week {week_nr}"
+                }
                 DatePicker {
                     on_selection_change: move |date| selected_date.set(date)
                 },
+                div {
+                    class: "flex gap-2",
+                    button {
+                        class: "button",
+                        "data-style": "outline",
+                        onclick: move |_| dialog.set(Some(ScheduleDialog::Capacity)),
+                        "Capacity"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "outline",
+                        onclick: move |_| dialog.set(Some(ScheduleDialog::Rebalance)),
+                        "Rebalance week"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "outline",
+                        onclick: move |_| export_week(week_start()),
+                        "Export .ics"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "outline",
+                        title: "Move open tasks from past days forward",
+                        onclick: move |_| rollover(),
+                        "Roll over"
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "outline",
+                        onclick: move |_| dialog.set(Some(ScheduleDialog::Preferences)),
+                        "Preferences"
+                    }
+                }
+                div {
+                    class: "flex gap-2 mt-2",
+                    for option in ScheduleView::ALL {
+                        button {
+                            class: "button",
+                            "data-style": if view() == option { "primary" } else { "ghost" },
+                            onclick: move |_| view.set(option),
+                            "{option.label()}"
+                        }
+                    }
+                }
             }
 
             div {
-                WeeklySchedule {
-                    selected_date: selected_date()
+                match view() {
+                    ScheduleView::Day => rsx! {
+                        DailySchedule {
+                            key: "{selected_date()}",
+                            day: selected_date(),
+                            available: capacity_on(&capacities.read(), selected_date()),
+                        }
+                    },
+                    ScheduleView::Week => rsx! {
+                        WeeklySchedule {
+                            selected_date: selected_date(),
+                            capacities: capacities.read().clone(),
+                        }
+                    },
+                    ScheduleView::Month => rsx! {
+                        MonthView { selected_date: selected_date() }
+                    },
+                    ScheduleView::Agenda => rsx! {
+                        AgendaView { selected_date: selected_date() }
+                    },
+                    ScheduleView::DayTimeline => rsx! {
+                        TimelineView { days: vec![selected_date()] }
+                    },
+                    ScheduleView::WeekTimeline => rsx! {
+                        TimelineView { days: week_days() }
+                    },
+                }
+            }
+
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: dialog().is_some(),
+                on_open_change: move |v: bool| if !v { dialog.set(None) },
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if dialog().is_some() { "0" } else { "-1" },
+                        onclick: move |_| dialog.set(None),
+                        "×"
+                    }
+                    match dialog() {
+                        Some(ScheduleDialog::Capacity) => rsx! {
+                            CapacityManager { selected_date: selected_date(), capacities: capacities }
+                        },
+                        Some(ScheduleDialog::Rebalance) => rsx! {
+                            RebalancePanel { week_start: week_start() }
+                        },
+                        Some(ScheduleDialog::Preferences) => rsx! {
+                            PreferencesEditor {}
+                        },
+                        None => rsx! {},
+                    }
                 }
             }
         }
     }
 }
 
+fn rollover() {
+    spawn(async move {
+        match server::rollover_tasks().await {
+            Ok(report) if report.rolled_over > 0 => *TASKS_VERSION.write() += 1,
+            Ok(_) => {},
+            Err(e) => eprintln!("Failed to roll over tasks: {}", e),
+        }
+    });
+}
+
+/// Downloads the week starting at `week_start` as an iCalendar file.
+fn export_week(week_start: NaiveDate) {
+    spawn(async move {
+        let week_end = week_start.checked_add_days(Days::new(6)).unwrap_or(week_start);
+        match server::export_ics(week_start, week_end).await {
+            Ok(ics) => {
+                let download = document::eval(&format!(r#"
+                    const ics = await dioxus.recv();
+                    const url = URL.createObjectURL(new Blob([ics], {{ type: "text/calendar" }}));
+                    const link = document.createElement("a");
+                    link.href = url;
+                    link.download = "planner-{}.ics";
+                    link.click();
+                    URL.revokeObjectURL(url);
+                "#, week_start));
+                if let Err(e) = download.send(ics) {
+                    eprintln!("Failed to download export: {:?}", e);
+                }
+            },
+            Err(e) => eprintln!("Failed to export week: {}", e),
+        }
+    });
+}
+
+/// The seven days of the week of `selected_date`, whose tasks are fetched together.
 #[component]
-fn WeeklySchedule(selected_date: NaiveDate) -> Element {
-    let first_weekday = selected_date.week(Weekday::Mon).first_day();
+fn WeeklySchedule(selected_date: NaiveDate, capacities: Vec<Capacity>) -> Element {
+    let first_weekday = PREFERENCES.read().week_of(selected_date);
+    let days: Vec<NaiveDate> = first_weekday.iter_days().take(7).collect();
+    let week = use_tasks_in_range(first_weekday, days[days.len() - 1]);
 
     rsx! {
         div {
             class: "flex gap-2 justify-between",
-            for i in 0..7 {
+            for day in days {
                 DailySchedule {
-                    key: "{first_weekday.checked_add_days(Days::new(i)).unwrap()}",
-                    day: first_weekday.checked_add_days(Days::new(i)).unwrap()}
+                    key: "{day}",
+                    day: day,
+                    available: capacity_on(&capacities, day),
+                    preloaded: week.read().preloaded(day),
+                }
             }
         }
     }
 }
 
 #[component]
-fn DailySchedule(day: NaiveDate) -> Element {
+fn DailySchedule(day: NaiveDate, available: i32, #[props(default)] preloaded: Option<PreloadedTasks>) -> Element {
+    let mut planned = use_signal(|| 0);
+
     rsx! {
         div {
             class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
-            "{day.weekday()} {day}",
-            ItemList{ filter: TaskFilter {scheduled_date: Some(day), backlog_id: None} }
+            "{PREFERENCES.read().format_day(day)}",
+            CapacityIndicator { planned: planned(), available: available }
+            ItemList {
+                filter: TaskFilter::day(day),
+                on_tasks_change: move |tasks: Vec<Task>| planned.set(planned_minutes(&tasks)),
+                preloaded: preloaded,
+            }
         }
-    }}
\ No newline at end of file
+    }}
@@ -0,0 +1,102 @@
+use dioxus::prelude::*;
+use super::item::ItemList;
+use super::undo::is_undo_shortcut;
+use super::tag::{TAGS, TagManager};
+use super::preferences::PREFERENCES;
+use crate::backend::server;
+use crate::backend::model::{Role, TaskFilter};
+use crate::backend::query::parse_query;
+use dioxus_primitives::dialog::{DialogContent, DialogRoot};
+
+/// Search box taking the task query language, e.g. `role:work is:open due<+7d`.
+#[component]
+pub fn SearchApp() -> Element {
+    let mut input = use_signal(|| String::new());
+    let mut active: Signal<Option<(String, TaskFilter)>> = use_signal(|| None);
+    let mut query_error: Signal<Option<String>> = use_signal(|| None);
+    let mut roles: Signal<Vec<Role>> = use_signal(|| vec![]);
+    let mut tags_open = use_signal(|| false);
+
+    use_hook(|| {
+        spawn(async move {
+            match server::get_roles().await {
+                Ok(fetched) => roles.set(fetched),
+                Err(e) => eprintln!("Failed to fetch roles: {}", e),
+            }
+        });
+    });
+
+    let mut search_fn = move || {
+        let query = input.read().trim().to_string();
+        if query.is_empty() {
+            active.set(None);
+            query_error.set(None);
+            return;
+        }
+        match parse_query(&query, PREFERENCES.read().today(), &roles.read(), &TAGS.read()) {
+            Ok(filter) => {
+                active.set(Some((query, filter)));
+                query_error.set(None);
+            },
+            Err(e) => query_error.set(Some(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div {
+            class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
+            input {
+                class: "inbox-input w-full",
+                r#type: "search",
+                placeholder: "Search, e.g. role:work tag:quick-win is:open due<+7d",
+                value: "{input}",
+                oninput: move |evt| input.set(evt.value()),
+                onkeydown: move |evt| {
+                    if is_undo_shortcut(&evt) {
+                        evt.stop_propagation();
+                        return;
+                    }
+                    match evt.key() {
+                        Key::Enter => search_fn(),
+                        Key::Escape => {
+                            input.set(String::new());
+                            search_fn();
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            if let Some(message) = query_error() {
+                div { class: "query-error", "{message}" }
+            }
+            if let Some((query, filter)) = active() {
+                ItemList {
+                    key: "{query}",
+                    filter: filter,
+                }
+            }
+            button {
+                class: "button",
+                "data-style": "ghost",
+                onclick: move |_| tags_open.set(true),
+                "Manage tags"
+            }
+            DialogRoot {
+                class: "dialog-backdrop",
+                open: tags_open(),
+                on_open_change: move |v| tags_open.set(v),
+                DialogContent {
+                    class: "dialog",
+                    button {
+                        class: "dialog-close",
+                        aria_label: "Close",
+                        tabindex: if tags_open() { "0" } else { "-1" },
+                        onclick: move |_| tags_open.set(false),
+                        "×"
+                    }
+                    TagManager {}
+                }
+            }
+        }
+    }
+}
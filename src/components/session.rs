@@ -0,0 +1,69 @@
+use dioxus::prelude::*;
+use crate::backend::server;
+use crate::backend::model::User;
+use crate::backend::validation::error_message;
+
+/// Who the browser is signed in as: `None` while checking, `Some(None)` when signed out.
+pub static SIGNED_IN: GlobalSignal<Option<Option<User>>> = Signal::global(|| None);
+
+pub fn refresh_session() {
+    spawn(async move {
+        match server::signed_in_user().await {
+            Ok(user) => *SIGNED_IN.write() = Some(user),
+            Err(e) => eprintln!("Failed to check session: {}", e),
+        }
+    });
+}
+
+/// Asks for the token printed by `planner sign-in <name>`.
+#[component]
+pub fn SignIn() -> Element {
+    let mut token = use_signal(String::new);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let sign_in_fn = move || {
+        let entered = token.read().clone();
+        if entered.trim().is_empty() {
+            return;
+        }
+        spawn(async move {
+            match server::sign_in(entered).await {
+                Ok(user) => {
+                    error.set(None);
+                    *SIGNED_IN.write() = Some(Some(user));
+                },
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-col gap-2 p-4 w-96",
+            label {
+                class: "flex flex-col gap-1",
+                "Sign-in token"
+                input {
+                    class: "inbox-input",
+                    r#type: "password",
+                    value: "{token}",
+                    oninput: move |evt| token.set(evt.value()),
+                    onkeydown: move |evt| if evt.key() == Key::Enter { sign_in_fn() },
+                }
+            }
+            button { class: "button", "data-style": "primary", onclick: move |_| sign_in_fn(), "Sign in" }
+            if let Some(message) = error() {
+                div { class: "query-error", "{message}" }
+            }
+        }
+    }
+}
+
+pub fn sign_out() {
+    spawn(async move {
+        match server::sign_out().await {
+            Ok(()) => *SIGNED_IN.write() = Some(None),
+            Err(e) => eprintln!("Failed to sign out: {}", e),
+        }
+    });
+}
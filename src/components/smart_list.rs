@@ -0,0 +1,84 @@
+use dioxus::prelude::*;
+use super::item::ItemList;
+use super::undo::{TASKS_VERSION, show_undo_toast};
+use super::preferences::PREFERENCES;
+use crate::backend::server;
+use crate::backend::model::SmartList;
+use crate::backend::validation::error_message;
+
+/// Built-in views such as Today and Overdue, shown one at a time below the inbox.
+#[component]
+pub fn SmartListApp() -> Element {
+    let mut selected = use_signal(|| SmartList::Today);
+    let preferences = PREFERENCES.read().clone();
+    let today = preferences.today();
+
+    rsx! {
+        div {
+            class: "flex-1 border border-gray-400 bg-gray-100 text-center p-4",
+            div {
+                class: "flex flex-wrap gap-1 justify-center mb-2",
+                for list in SmartList::ALL {
+                    button {
+                        class: "button",
+                        "data-style": if selected() == list { "primary" } else { "ghost" },
+                        onclick: move |_| selected.set(list),
+                        "{list.label()}"
+                    }
+                }
+            }
+            if selected() == SmartList::Archived {
+                ArchiveCompleted {}
+            }
+            ItemList {
+                key: "{selected().label()}",
+                filter: selected().filter(today, &preferences),
+            }
+        }
+    }
+}
+
+/// Puts away tasks that were completed a while ago.
+#[component]
+fn ArchiveCompleted() -> Element {
+    let mut days = use_signal(|| 30);
+    let mut error: Signal<Option<String>> = use_signal(|| None);
+
+    let archive_fn = move |_| {
+        spawn(async move {
+            match server::archive_completed(days()).await {
+                Ok(count) => {
+                    error.set(None);
+                    show_undo_toast(format!("Archived {} tasks", count));
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => error.set(Some(error_message(&e))),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "flex flex-wrap gap-1 justify-center items-center mb-2",
+            "Archive tasks completed more than"
+            input {
+                class: "inbox-input",
+                r#type: "number",
+                min: "0",
+                style: "width: 5em;",
+                aria_label: "Days",
+                value: "{days}",
+                oninput: move |evt| {
+                    if let Ok(value) = evt.value().parse() {
+                        days.set(value);
+                    }
+                }
+            }
+            "days ago"
+            button { class: "button", "data-style": "outline", onclick: archive_fn, "Archive" }
+        }
+        if let Some(message) = error() {
+            div { class: "query-error", "{message}" }
+        }
+    }
+}
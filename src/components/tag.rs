@@ -0,0 +1,274 @@
+use dioxus::prelude::*;
+use std::collections::HashMap;
+use crate::backend::server;
+use crate::backend::model::{Id, Tag};
+use dioxus_primitives::dialog::{DialogDescription, DialogTitle};
+use super::undo::{TASKS_VERSION, is_undo_shortcut};
+
+/// All tags, shared by every view so they show the same colors.
+pub static TAGS: GlobalSignal<Vec<Tag>> = Signal::global(|| vec![]);
+/// Tag ids per task, filled in as lists fetch their tasks.
+pub static TASK_TAGS: GlobalSignal<HashMap<Id, Vec<Id>>> = Signal::global(HashMap::new);
+
+pub fn refresh_tags() {
+    spawn(async move {
+        match server::get_tags().await {
+            Ok(fetched) => *TAGS.write() = fetched,
+            Err(e) => eprintln!("Failed to fetch tags: {}", e),
+        }
+    });
+}
+
+/// Fetches the tags of tasks that were just loaded into a view.
+pub fn load_task_tags(task_ids: Vec<Id>) {
+    if task_ids.is_empty() {
+        return;
+    }
+    spawn(async move {
+        match server::get_task_tags(task_ids.clone()).await {
+            Ok(fetched) => {
+                let mut task_tags = TASK_TAGS.write();
+                for task_id in task_ids {
+                    task_tags.insert(task_id, vec![]);
+                }
+                for tagged in fetched {
+                    task_tags.entry(tagged.task_id).or_default().push(tagged.tag_id);
+                }
+            },
+            Err(e) => eprintln!("Failed to fetch task tags: {}", e),
+        }
+    });
+}
+
+fn tags_of(task_id: Id) -> Vec<Tag> {
+    let ids = TASK_TAGS.read().get(&task_id).cloned().unwrap_or_default();
+    TAGS.read().iter().filter(|tag| ids.contains(&tag.id)).cloned().collect()
+}
+
+/// Colored dots for the tags of a task, for places too small for full chips.
+#[component]
+pub fn TagDots(task_id: Id) -> Element {
+    rsx! {
+        for tag in tags_of(task_id) {
+            span {
+                key: "{tag.id.0}",
+                class: "tag-dot",
+                title: "{tag.name}",
+                style: "background-color: {tag.color};",
+            }
+        }
+    }
+}
+
+/// Tag chips of a task, with a button to take each off and an input to add more.
+#[component]
+pub fn TagEditor(task_id: Id) -> Element {
+    let mut adding = use_signal(|| false);
+    let mut new_tag = use_signal(|| String::new());
+
+    let add_tag_fn = move || {
+        let name = new_tag.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            match server::add_task_tag(task_id, name).await {
+                Ok(tag) => {
+                    if !TAGS.read().iter().any(|t| t.id == tag.id) {
+                        TAGS.write().push(tag.clone());
+                    }
+                    let mut task_tags = TASK_TAGS.write();
+                    let ids = task_tags.entry(task_id).or_default();
+                    if !ids.contains(&tag.id) {
+                        ids.push(tag.id);
+                    }
+                    drop(task_tags);
+                    new_tag.set(String::new());
+                    adding.set(false);
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to tag task: {}", e),
+            }
+        });
+    };
+
+    let remove_tag_fn = move |tag_id: Id| {
+        spawn(async move {
+            match server::remove_task_tag(task_id, tag_id).await {
+                Ok(_) => {
+                    if let Some(ids) = TASK_TAGS.write().get_mut(&task_id) {
+                        ids.retain(|id| *id != tag_id);
+                    }
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to remove tag: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        for tag in tags_of(task_id) {
+            span {
+                key: "{tag.id.0}",
+                class: "tag-chip",
+                style: "background-color: {tag.color};",
+                "{tag.name}"
+                button {
+                    class: "tag-remove",
+                    aria_label: "Remove tag",
+                    onclick: move |_| remove_tag_fn(tag.id),
+                    "×"
+                }
+            }
+        }
+        if adding() {
+            input {
+                class: "tag-input",
+                r#type: "text",
+                placeholder: "Tag",
+                list: "tag-names-{task_id.0}",
+                autofocus: true,
+                value: "{new_tag}",
+                oninput: move |evt| new_tag.set(evt.value()),
+                onblur: move |_| adding.set(false),
+                onkeydown: move |evt| {
+                    if is_undo_shortcut(&evt) {
+                        evt.stop_propagation();
+                        return;
+                    }
+                    match evt.key() {
+                        Key::Enter => add_tag_fn(),
+                        Key::Escape => {
+                            new_tag.set(String::new());
+                            adding.set(false);
+                        },
+                        _ => {}
+                    }
+                }
+            }
+            datalist {
+                id: "tag-names-{task_id.0}",
+                for tag in TAGS.read().clone() {
+                    option { key: "{tag.id.0}", value: "{tag.name}" }
+                }
+            }
+        } else {
+            button {
+                class: "button",
+                "data-style": "ghost",
+                aria_label: "Add tag",
+                onclick: move |_| adding.set(true),
+                "#"
+            }
+        }
+    }
+}
+
+/// Renames, recolors and deletes tags.
+#[component]
+pub fn TagManager() -> Element {
+    let mut new_tag = use_signal(|| String::new());
+
+    let create_tag_fn = move || {
+        let name = new_tag.read().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        spawn(async move {
+            match server::create_tag(name, None).await {
+                Ok(tag) => {
+                    TAGS.write().push(tag);
+                    new_tag.set(String::new());
+                },
+                Err(e) => eprintln!("Failed to create tag: {}", e),
+            }
+        });
+    };
+
+    let update_tag_fn = move |tag: Tag| {
+        spawn(async move {
+            match server::update_tag(tag).await {
+                Ok(updated) => {
+                    if let Some(t) = TAGS.write().iter_mut().find(|t| t.id == updated.id) {
+                        *t = updated;
+                    }
+                },
+                Err(e) => eprintln!("Failed to update tag: {}", e),
+            }
+        });
+    };
+
+    let delete_tag_fn = move |tag_id: Id| {
+        spawn(async move {
+            match server::delete_tag(tag_id).await {
+                Ok(_) => {
+                    TAGS.write().retain(|t| t.id != tag_id);
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to delete tag: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        DialogTitle {
+            class: "dialog-title",
+            "Tags"
+        }
+        DialogDescription {
+            for tag in TAGS.read().clone() {
+                div {
+                    key: "{tag.id.0}",
+                    class: "flex items-center gap-2",
+                    input {
+                        r#type: "color",
+                        aria_label: "Color",
+                        value: "{tag.color}",
+                        onchange: {
+                            let tag = tag.clone();
+                            move |evt: FormEvent| update_tag_fn(Tag { color: evt.value(), ..tag.clone() })
+                        },
+                    }
+                    input {
+                        class: "inbox-input flex-1",
+                        r#type: "text",
+                        value: "{tag.name}",
+                        onchange: {
+                            let tag = tag.clone();
+                            move |evt: FormEvent| {
+                                if !evt.value().trim().is_empty() {
+                                    update_tag_fn(Tag { name: evt.value(), ..tag.clone() });
+                                }
+                            }
+                        },
+                    }
+                    button {
+                        class: "button",
+                        "data-style": "ghost",
+                        onclick: move |_| delete_tag_fn(tag.id),
+                        "Delete"
+                    }
+                }
+            }
+            div {
+                class: "flex mt-2",
+                input {
+                    class: "inbox-input flex-1",
+                    r#type: "text",
+                    placeholder: "New tag, e.g. waiting-on",
+                    value: "{new_tag}",
+                    oninput: move |evt| new_tag.set(evt.value()),
+                    onkeydown: move |evt| match evt.key() {
+                        Key::Enter => create_tag_fn(),
+                        Key::Escape => new_tag.set(String::new()),
+                        _ => {}
+                    }
+                }
+                button {
+                    class: "inbox-button",
+                    onclick: move |_| create_tag_fn(), "Add"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use dioxus::prelude::*;
+use chrono::{Datelike, NaiveDate, NaiveTime, TimeDelta, Timelike};
+use crate::backend::server;
+use crate::backend::model::{Task, TaskPatch, overlapping_tasks};
+use crate::backend::validation::{error_message, is_conflict};
+use super::item::DRAGGING_ITEM;
+use super::undo::{TASKS_VERSION, show_undo_toast};
+use super::tag::TagDots;
+use super::range::use_tasks_in_range;
+use super::dependency::rescheduled_message;
+use super::preferences::PREFERENCES;
+
+const FIRST_HOUR: u32 = 6;
+const LAST_HOUR: u32 = 22;
+const SLOT_MINUTES: u32 = 30;
+const SLOT_HEIGHT_PX: u32 = 20;
+/// Length of a new time block for tasks without an estimate.
+const DEFAULT_BLOCK_MINUTES: i64 = 60;
+
+#[derive(Clone, PartialEq)]
+enum TimelineDrag {
+    Move(Task),
+    Resize(Task),
+}
+
+static TIMELINE_DRAG: GlobalSignal<Option<TimelineDrag>> = Signal::global(|| None);
+
+fn start_drag(drag: TimelineDrag) {
+    // A list drag may still be lingering from an earlier drop elsewhere
+    *DRAGGING_ITEM.write() = None;
+    *TIMELINE_DRAG.write() = Some(drag);
+}
+
+fn slots() -> impl Iterator<Item = NaiveTime> {
+    (FIRST_HOUR * 60..LAST_HOUR * 60)
+        .step_by(SLOT_MINUTES as usize)
+        .filter_map(|m| NaiveTime::from_hms_opt(m / 60, m % 60, 0))
+}
+
+fn hhmm(time: NaiveTime) -> String {
+    time.format("%H:%M").to_string()
+}
+
+fn offset_px(time: NaiveTime) -> i64 {
+    let minutes = time.hour() as i64 * 60 + time.minute() as i64 - FIRST_HOUR as i64 * 60;
+    minutes * SLOT_HEIGHT_PX as i64 / SLOT_MINUTES as i64
+}
+
+/// The task after dropping it, or its resize handle, on the slot starting at `slot`.
+fn dropped_on_slot(drag: TimelineDrag, day: NaiveDate, slot: NaiveTime) -> Option<Task> {
+    match drag {
+        TimelineDrag::Move(mut task) => {
+            let duration = match task.time_block() {
+                Some((start, end)) => end - start,
+                None => TimeDelta::minutes(task.estimate_minutes.map(i64::from).unwrap_or(DEFAULT_BLOCK_MINUTES)),
+            };
+            let (end, overflow) = slot.overflowing_add_signed(duration);
+            task.scheduled_date = Some(day);
+            task.start_time = Some(slot);
+            // Blocks can't run past midnight
+            task.end_time = Some(if overflow == 0 { end } else { NaiveTime::from_hms_opt(23, 59, 0)? });
+            Some(task)
+        },
+        TimelineDrag::Resize(mut task) => {
+            let (start, _) = task.time_block()?;
+            if task.scheduled_date != Some(day) || slot < start {
+                return None;
+            }
+            let (end, overflow) = slot.overflowing_add_signed(TimeDelta::minutes(SLOT_MINUTES as i64));
+            task.end_time = Some(if overflow == 0 { end } else { NaiveTime::from_hms_opt(23, 59, 0)? });
+            Some(task)
+        },
+    }
+}
+
+/// Hour grid for one or more consecutive days where tasks can be dragged into time slots and
+/// resized. The tasks of all days are fetched at once.
+#[component]
+pub fn TimelineView(days: Vec<NaiveDate>) -> Element {
+    let first = days.first().copied().unwrap_or_default();
+    let range = use_tasks_in_range(first, days.last().copied().unwrap_or(first));
+
+    rsx! {
+        div {
+            class: "timeline",
+            div {
+                class: "timeline-hours",
+                div { class: "timeline-untimed-header" }
+                for slot in slots().filter(|s| s.minute() == 0) {
+                    div {
+                        key: "{slot}",
+                        class: "timeline-hour",
+                        style: "height: {SLOT_HEIGHT_PX * 60 / SLOT_MINUTES}px;",
+                        "{hhmm(slot)}"
+                    }
+                }
+            }
+            for day in days {
+                TimelineDay { key: "{day}", day: day, tasks: range.read().on(day) }
+            }
+        }
+    }
+}
+
+#[component]
+fn TimelineDay(day: NaiveDate, tasks: Vec<Task>) -> Element {
+    let drop_fn = move |slot: NaiveTime| {
+        let drag = TIMELINE_DRAG.write().take()
+            .or_else(|| DRAGGING_ITEM.read().clone().map(TimelineDrag::Move));
+        let Some(task) = drag.and_then(|drag| dropped_on_slot(drag, day, slot)) else {
+            return;
+        };
+        let patch = TaskPatch {
+            scheduled_date: Some(task.scheduled_date),
+            start_time: Some(task.start_time),
+            end_time: Some(task.end_time),
+            ..TaskPatch::new(&task)
+        };
+        spawn(async move {
+            match server::patch_task(patch).await {
+                Ok(moved) => {
+                    *TASKS_VERSION.write() += 1;
+                    show_undo_toast(rescheduled_message(moved.id, "Task rescheduled").await);
+                },
+                Err(e) if is_conflict(&e) => {
+                    show_undo_toast(error_message(&e));
+                    *TASKS_VERSION.write() += 1;
+                },
+                Err(e) => eprintln!("Failed to reschedule task: {}", e),
+            }
+        });
+    };
+
+    let overlapping = overlapping_tasks(&tasks);
+    let timed: Vec<Task> = tasks.iter().filter(|t| t.time_block().is_some()).cloned().collect();
+    let untimed: Vec<Task> = tasks.iter().filter(|t| t.time_block().is_none()).cloned().collect();
+
+    rsx! {
+        div {
+            class: "timeline-day",
+            div {
+                class: "timeline-untimed",
+                div { class: "timeline-day-title", "{PREFERENCES.read().locale.weekday_short(day.weekday())} {day.day()}" }
+                for task in untimed {
+                    div {
+                        key: "{task.id.0}",
+                        class: "timeline-chip",
+                        draggable: "true",
+                        ondragstart: {
+                            let task = task.clone();
+                            move |_| start_drag(TimelineDrag::Move(task.clone()))
+                        },
+                        ondragend: move |_| *TIMELINE_DRAG.write() = None,
+                        TagDots { task_id: task.id }
+                        "{task.title}"
+                    }
+                }
+            }
+            div {
+                class: "timeline-slots",
+                for slot in slots() {
+                    div {
+                        key: "{slot}",
+                        class: if slot.minute() == 0 { "timeline-slot hour" } else { "timeline-slot" },
+                        style: "height: {SLOT_HEIGHT_PX}px;",
+                        ondragover: move |e| e.prevent_default(),
+                        ondrop: move |_| drop_fn(slot),
+                    }
+                }
+                for task in timed {
+                    TimeBlock {
+                        key: "{task.id.0}",
+                        overlapping: overlapping.contains(&task.id),
+                        task: task,
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn TimeBlock(task: Task, overlapping: bool) -> Element {
+    let Some((start, end)) = task.time_block() else {
+        return rsx! {};
+    };
+    let top = offset_px(start);
+    let height = offset_px(end) - top;
+    let class = if overlapping { "timeline-block overlapping" } else { "timeline-block" };
+    let title = if overlapping { "Overlaps another block" } else { "" };
+
+    rsx! {
+        div {
+            class: "{class}",
+            title: "{title}",
+            style: "top: {top}px; height: {height}px;",
+            draggable: "true",
+            ondragstart: {
+                let task = task.clone();
+                move |_| start_drag(TimelineDrag::Move(task.clone()))
+            },
+            ondragend: move |_| *TIMELINE_DRAG.write() = None,
+            span {
+                TagDots { task_id: task.id }
+                "{hhmm(start)}–{hhmm(end)} {task.title}"
+            }
+            div {
+                class: "timeline-resize",
+                draggable: "true",
+                ondragstart: {
+                    let task = task.clone();
+                    move |e: DragEvent| {
+                        e.stop_propagation();
+                        start_drag(TimelineDrag::Resize(task.clone()));
+                    }
+                },
+                ondragend: move |_| *TIMELINE_DRAG.write() = None,
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use dioxus::prelude::*;
+use crate::backend::server;
+
+/// Bumped whenever tasks change outside of the list showing them, so lists know to refetch.
+pub static TASKS_VERSION: GlobalSignal<u64> = Signal::global(|| 0);
+
+static TOAST: GlobalSignal<Option<String>> = Signal::global(|| None);
+static TOAST_GENERATION: GlobalSignal<u64> = Signal::global(|| 0);
+
+const TOAST_DURATION_MS: u32 = 5000;
+
+/// Shows a toast offering to undo the change that was just made.
+pub fn show_undo_toast(message: impl Into<String>) {
+    *TOAST.write() = Some(message.into());
+    *TOAST_GENERATION.write() += 1;
+    let generation = *TOAST_GENERATION.peek();
+
+    spawn(async move {
+        let _ = document::eval(&format!("await new Promise(r => setTimeout(r, {})); return null;", TOAST_DURATION_MS)).await;
+        // A newer toast keeps its own timer
+        if *TOAST_GENERATION.peek() == generation {
+            *TOAST.write() = None;
+        }
+    });
+}
+
+pub fn undo() {
+    spawn(async move {
+        match server::undo_last_change().await {
+            Ok(undone) if !undone.is_empty() => *TASKS_VERSION.write() += 1,
+            Ok(_) => {},
+            Err(e) => eprintln!("Failed to undo: {}", e),
+        }
+        *TOAST.write() = None;
+    });
+}
+
+pub fn redo() {
+    spawn(async move {
+        match server::redo_last_change().await {
+            Ok(redone) if !redone.is_empty() => *TASKS_VERSION.write() += 1,
+            Ok(_) => {},
+            Err(e) => eprintln!("Failed to redo: {}", e),
+        }
+    });
+}
+
+/// Whether a key press is Ctrl+Z or Ctrl+Shift+Z (or Cmd on macOS).
+pub fn is_undo_shortcut(evt: &KeyboardEvent) -> bool {
+    let modifiers = evt.modifiers();
+    (modifiers.ctrl() || modifiers.meta()) && evt.code() == Code::KeyZ
+}
+
+/// Application-wide undo/redo key handler. Text inputs stop these keys from bubbling up here
+/// while they are being edited, so their native undo keeps working.
+pub fn handle_undo_shortcut(evt: KeyboardEvent) {
+    if !is_undo_shortcut(&evt) {
+        return;
+    }
+    evt.prevent_default();
+    if evt.modifiers().shift() {
+        redo();
+    } else {
+        undo();
+    }
+}
+
+#[component]
+pub fn UndoToast() -> Element {
+    rsx! {
+        if let Some(message) = TOAST.read().clone() {
+            div {
+                class: "undo-toast",
+                role: "status",
+                span { "{message}" }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    onclick: move |_| undo(),
+                    "Undo"
+                }
+                button {
+                    class: "button",
+                    "data-style": "ghost",
+                    aria_label: "Dismiss",
+                    onclick: move |_| *TOAST.write() = None,
+                    "×"
+                }
+            }
+        }
+    }
+}
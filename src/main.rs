@@ -5,41 +5,109 @@ mod backend;
 use crate::components::*;
 
 fn main() {
+    #[cfg(feature = "server")]
+    {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if let Some(code) = backend::cli::run(&args) {
+            std::process::exit(code);
+        }
+        backend::rollover::start_scheduler();
+    }
+
     dioxus::launch(app);
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+    Planner,
+    Projects,
+}
+
 fn app() -> Element {
+    use_hook(refresh_session);
+
     rsx! {
         document::Stylesheet { href: asset!("/assets/tailwind.css") }
         document::Stylesheet { href: asset!("/assets/theme.css") }
         document::Link { rel: "icon", href: asset!("/assets/favicon.ico") }
         document::Title { "Planner" }
 
-        div {
-            class: "grid grid-cols-3 gap-4 p-4 h-screen",
-            
-            // Left column: Inbox
-            div {
-                class: "col-span-1 overflow-y-auto",
-                InboxApp {}
+        match SIGNED_IN.read().clone() {
+            Some(Some(_)) => rsx! { Planner {} },
+            Some(None) => rsx! { SignIn {} },
+            None => rsx! {},
+        }
+    }
+}
+
+/// The app for a signed-in user.
+#[component]
+fn Planner() -> Element {
+    let mut page = use_signal(|| Page::Planner);
+    use_hook(refresh_tags);
+    use_hook(refresh_projects);
+    use_hook(refresh_preferences);
+
+    rsx! {
+        nav {
+            class: "flex gap-2 px-4 pt-4",
+            button {
+                class: "button",
+                "data-style": if page() == Page::Planner { "primary" } else { "ghost" },
+                onclick: move |_| page.set(Page::Planner),
+                "Planner"
             }
+            button {
+                class: "button",
+                "data-style": if page() == Page::Projects { "primary" } else { "ghost" },
+                onclick: move |_| page.set(Page::Projects),
+                "Projects"
+            }
+            button {
+                class: "button ml-auto",
+                "data-style": "ghost",
+                onclick: move |_| sign_out(),
+                "Sign out"
+            }
+        }
 
-            // Right column: vertical layout with Schedule on top, Backlog below
+        if page() == Page::Projects {
+            ProjectsPage {}
+        } else {
             div {
-                class: "col-span-2 flex flex-col min-h-0",
-                
-                // Schedule takes all available space
+                class: "grid grid-cols-3 gap-4 p-4 h-screen",
+                // Focusable so undo/redo shortcuts work without anything else focused
+                tabindex: "-1",
+                onkeydown: handle_undo_shortcut,
+            
+                // Left column: search and Inbox with the smart lists and saved views below them
                 div {
-                    class: "flex-grow min-h-0 overflow-y-auto",
-                    ScheduleApp {}
+                    class: "col-span-1 overflow-y-auto flex flex-col gap-4",
+                    SearchApp {}
+                    InboxApp {}
+                    SmartListApp {}
+                    SavedViewsApp {}
                 }
 
-                // Backlog fits its content
+                // Right column: vertical layout with Schedule on top, Backlog below
                 div {
-                    class: "overflow-y-auto",
-                    BacklogApp {}
+                    class: "col-span-2 flex flex-col min-h-0",
+                
+                    // Schedule takes all available space
+                    div {
+                        class: "flex-grow min-h-0 overflow-y-auto",
+                        ScheduleApp {}
+                    }
+
+                    // Backlog fits its content
+                    div {
+                        class: "overflow-y-auto",
+                        BacklogApp {}
+                    }
                 }
             }
         }
+
+        UndoToast {}
     }
 }